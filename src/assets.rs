@@ -0,0 +1,46 @@
+use serde::Deserialize;
+use wasm_bindgen::UnwrapThrowExt;
+
+use crate::{
+    audio::{fetch_optional_json, set_builtin_sounds, Sound, SoundEntry},
+    diagnostics::report_error,
+    ducks::{set_ducks, DuckEntry},
+};
+
+/// Copy of `assets.json` as it was at build time, for when the served one can't be loaded.
+const BUNDLED: &str = include_str!("../assets/assets.json");
+
+/// Contents of `assets.json`, which lists the built-in sounds and the ducks. Being loaded at
+/// runtime, both can be added to without building the app again.
+#[derive(Deserialize)]
+struct Manifest {
+    sounds: Vec<SoundEntry>,
+    ducks: Vec<DuckEntry>,
+}
+
+/// Load the asset manifest, falling back to the bundled one if it doesn't exist or is unusable.
+/// Must be done before anything uses the built-in sounds or the ducks.
+pub async fn load_assets() {
+    let manifest = match fetch_optional_json::<Manifest>("assets.json").await {
+        Ok(Some(manifest)) if manifest.sounds.is_empty() || manifest.ducks.is_empty() => {
+            report_error(
+                "assets",
+                "`assets.json` needs at least one sound and duck".to_owned(),
+            );
+            bundled()
+        }
+        Ok(Some(manifest)) => manifest,
+        Ok(None) => bundled(),
+        Err(e) => {
+            report_error("assets", format!("failed loading assets:\n{e:?}"));
+            bundled()
+        }
+    };
+
+    set_builtin_sounds(manifest.sounds.into_iter().map(Sound::leaked).collect());
+    set_ducks(manifest.ducks);
+}
+
+fn bundled() -> Manifest {
+    serde_json::from_str(BUNDLED).unwrap_throw()
+}
@@ -0,0 +1,865 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    iter,
+    rc::Rc,
+};
+
+use js_sys::{Array, ArrayBuffer, Uint8Array};
+use leptos::{create_trigger, prelude::*, window, Trigger};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    AnalyserNode, AudioBuffer, AudioContext, AudioContextState, AudioParam, Blob, BlobPropertyBag,
+    DomException, DynamicsCompressorNode, GainNode, HtmlAudioElement, Response, Url,
+};
+
+thread_local! {
+    static BUILTIN: Cell<&'static [Sound]> = const { Cell::new(&[]) };
+}
+
+/// Sounds of the built-in pack, which is always available.
+pub fn builtin_sounds() -> &'static [Sound] {
+    BUILTIN.get()
+}
+
+/// Replace the sounds of the built-in pack, which must not be empty.
+pub fn set_builtin_sounds(sounds: Vec<Sound>) {
+    BUILTIN.set(Box::leak(sounds.into_boxed_slice()));
+}
+
+/// Sound as listed in a manifest.
+#[derive(Deserialize)]
+pub struct SoundEntry {
+    /// File name without extension, relative to the `audio` folder.
+    name: String,
+    /// File extensions of the available encodings, in order of preference.
+    #[serde(default = "default_extensions")]
+    extensions: Vec<String>,
+}
+
+fn default_extensions() -> Vec<String> {
+    vec!["mp3".to_owned()]
+}
+
+/// Single sound, that may be available in several encodings under `audio/<name>.<extension>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sound {
+    pub name: &'static str,
+    /// File extensions of the available encodings, in order of preference.
+    extensions: &'static [&'static str],
+}
+
+impl Sound {
+    /// Sound that is only known at runtime, from the [asset](crate::assets) or
+    /// [pack](crate::packs) manifest. Like for [custom](Self::custom) sounds, the strings are
+    /// leaked.
+    pub fn leaked(SoundEntry { name, extensions }: SoundEntry) -> Self {
+        let extensions = extensions
+            .into_iter()
+            .map(|extension| &*Box::leak(extension.into_boxed_str()))
+            .collect::<Vec<_>>();
+        let name = &*Box::leak(name.into_boxed_str());
+        MANIFEST_NAMES.with_borrow_mut(|names| names.insert(name));
+
+        Self {
+            name,
+            extensions: Box::leak(extensions.into_boxed_slice()),
+        }
+    }
+
+    /// Sound that the user added, which only exists in memory as object `url`. The name is leaked
+    /// to live as long as the built-in ones, which is fine for the handful of sounds a user adds.
+    pub fn custom(name: String, url: String) -> Self {
+        let name = &*Box::leak(name.into_boxed_str());
+        PRELOADED.with_borrow_mut(|preloaded| preloaded.insert(name, url));
+
+        Self {
+            name,
+            extensions: &[],
+        }
+    }
+
+    /// Whether any manifest, including those of packs that aren't selected, has a sound called
+    /// `name`. Sounds are told apart by name, so custom sounds must not reuse these.
+    pub fn in_manifest(name: &str) -> bool {
+        MANIFEST_NAMES.with_borrow(|names| names.contains(name))
+    }
+
+    pub fn is_custom(self) -> bool {
+        self.extensions.is_empty()
+    }
+
+    /// Release the in-memory copy of a [custom](Self::custom) sound, after which it can't be
+    /// played anymore.
+    pub fn forget(self) {
+        if let Some(url) = PRELOADED.with_borrow_mut(|preloaded| preloaded.remove(self.name)) {
+            Url::revoke_object_url(&url).ok();
+        }
+    }
+
+    /// Sources of all encodings that the browser claims it can play, in order of preference. A
+    /// slice of the [sprite](load_sprite) or an already [preloaded](Self::preload) copy always
+    /// come first.
+    pub fn sources(self, audio: &HtmlAudioElement) -> impl Iterator<Item = String> {
+        let audio = audio.clone();
+        let sprite = SPRITE.with_borrow(|sprite| {
+            sprite.as_ref().and_then(|sprite| {
+                let cue = sprite.cues.get(self.name)?;
+                Some(format!(
+                    "{}#t={},{}",
+                    sprite.url,
+                    cue.start,
+                    cue.start + cue.duration
+                ))
+            })
+        });
+        let preloaded = PRELOADED.with_borrow(|preloaded| preloaded.get(self.name).cloned());
+
+        sprite.into_iter().chain(preloaded).chain(
+            self.extensions
+                .iter()
+                .filter(move |extension| {
+                    audio_mime(extension).is_some_and(|mime| !audio.can_play_type(mime).is_empty())
+                })
+                .map(move |extension| format!("audio/{}.{extension}", self.name)),
+        )
+    }
+
+    /// Download the preferred playable encoding into memory, so the first playback doesn't have to
+    /// wait for the network.
+    pub async fn preload(self, audio: &HtmlAudioElement) -> Result<(), JsValue> {
+        if self.in_sprite() {
+            return Ok(());
+        }
+        let Some(src) = self.sources(audio).next() else {
+            return Ok(());
+        };
+
+        let url = fetch_object_url(&fetch(&src).await?).await?;
+        PRELOADED.with_borrow_mut(|preloaded| preloaded.insert(self.name, url));
+
+        Ok(())
+    }
+
+    /// Decoded copy of the sound, for playback that is [scheduled](AudioGraph::schedule) ahead of
+    /// time. Every source is only decoded once, so the sounds of a sprite share a single buffer.
+    pub async fn decode(self, context: &AudioContext) -> Result<Clip, JsValue> {
+        let audio = HtmlAudioElement::new()?;
+        let mut error = JsValue::from("no playable source");
+
+        for src in self.sources(&audio).chain(iter::once_with(bundled_quack)) {
+            let url = src.split_once('#').map_or(src.as_str(), |(url, _)| url);
+            let buffer = match DECODED.with_borrow(|decoded| decoded.get(url).cloned()) {
+                Some(buffer) => buffer,
+                None => match decode_audio(context, url).await {
+                    Ok(buffer) => {
+                        DECODED.with_borrow_mut(|decoded| {
+                            decoded.insert(url.to_owned(), buffer.clone());
+                        });
+                        buffer
+                    }
+                    Err(e) => {
+                        error = e;
+                        continue;
+                    }
+                },
+            };
+            let (offset, end) = fragment(&src).unwrap_or((0.0, buffer.duration()));
+
+            return Ok(Clip {
+                buffer,
+                offset,
+                duration: end - offset,
+            });
+        }
+
+        Err(error)
+    }
+
+    pub fn in_sprite(self) -> bool {
+        SPRITE.with_borrow(|sprite| {
+            sprite
+                .as_ref()
+                .is_some_and(|sprite| sprite.cues.contains_key(self.name))
+        })
+    }
+}
+
+/// Relative weights for the random selection, by sound name. Sounds without an entry have a weight
+/// of [`DEFAULT_WEIGHT`].
+pub type SoundWeights = BTreeMap<String, u8>;
+
+pub const DEFAULT_WEIGHT: u8 = 1;
+pub const MAX_WEIGHT: u8 = 5;
+
+/// Weight of the `sound`, limited to [`MAX_WEIGHT`] as the stored weights may have been edited.
+pub fn weight(weights: &SoundWeights, sound: Sound) -> u8 {
+    weights
+        .get(sound.name)
+        .copied()
+        .unwrap_or(DEFAULT_WEIGHT)
+        .min(MAX_WEIGHT)
+}
+
+/// Sounds that are excluded from the random selection, by name.
+pub type DisabledSounds = BTreeSet<String>;
+
+/// Sounds that the user starred, by name. They play more often and can be picked on their own.
+pub type FavoriteSounds = BTreeSet<String>;
+
+/// Factor on the weight of favorite sounds.
+const FAVORITE_BOOST: usize = 2;
+
+/// Sounds of the current `pack`, followed by the `custom` ones.
+pub fn all_sounds(pack: &[Sound], custom: &[Sound]) -> Vec<Sound> {
+    pack.iter().chain(custom).copied().collect()
+}
+
+/// Sounds of `all` that take part in the random selection. If all of them are disabled, every
+/// sound does, as it would be odd if the duck fell silent.
+pub fn enabled_sounds(all: &[Sound], disabled: &DisabledSounds) -> Vec<Sound> {
+    let enabled = all
+        .iter()
+        .copied()
+        .filter(|sound| !disabled.contains(sound.name))
+        .collect::<Vec<_>>();
+
+    if enabled.is_empty() {
+        all.to_vec()
+    } else {
+        enabled
+    }
+}
+
+/// Pool to draw random sounds from, that contains every sound as often as its weight says, and
+/// favorites [more often](FAVORITE_BOOST). Sounds with a weight of 0 never play, unless all of them
+/// have it, like with [disabled sounds](enabled_sounds).
+pub fn weighted_pool(
+    sounds: &[Sound],
+    weights: &SoundWeights,
+    favorites: &FavoriteSounds,
+) -> Vec<Sound> {
+    let pool = sounds
+        .iter()
+        .flat_map(|&sound| {
+            let boost = if favorites.contains(sound.name) {
+                FAVORITE_BOOST
+            } else {
+                1
+            };
+            iter::repeat_n(sound, usize::from(weight(weights, sound)) * boost)
+        })
+        .collect::<Vec<_>>();
+
+    if pool.is_empty() {
+        sounds.to_vec()
+    } else {
+        pool
+    }
+}
+
+thread_local! {
+    /// Random numbers for picking sounds, apart from any other randomness like the reverb noise or
+    /// the pitch variation, so a [fixed seed](seed_selection) always leads to the same sounds.
+    static SELECTION: RefCell<fastrand::Rng> = RefCell::new(fastrand::Rng::new());
+}
+
+/// Make the random selection of sounds repeat the same sequence for the same `seed`.
+pub fn seed_selection(seed: u64) {
+    SELECTION.with_borrow_mut(|rng| rng.seed(seed));
+}
+
+/// Random sound out of `sounds`, drawn like the ones of a [`ShuffleBag`].
+pub fn choose_sound(sounds: Vec<Sound>) -> Option<Sound> {
+    SELECTION.with_borrow_mut(|rng| rng.choice(sounds))
+}
+
+/// Random selection without repeats. Every entry of the pool is drawn once, in random order,
+/// before the bag is refilled.
+#[derive(Clone, Debug, Default)]
+pub struct ShuffleBag {
+    remaining: Vec<Sound>,
+    last: Option<Sound>,
+}
+
+impl ShuffleBag {
+    /// Draw the next sound out of the `pool`, or `None` if the pool is empty. Sounds that left the
+    /// pool since the bag was filled are skipped.
+    pub fn draw(&mut self, pool: &[Sound]) -> Option<Sound> {
+        self.remaining.retain(|sound| pool.contains(sound));
+
+        if self.remaining.is_empty() {
+            self.remaining = pool.to_vec();
+            SELECTION.with_borrow_mut(|rng| rng.shuffle(&mut self.remaining));
+            // Sounds are drawn from the back, so keep the last one of the previous bag away from
+            // there, to not repeat it right after the refill.
+            let end = self.remaining.len().saturating_sub(1);
+            if end > 0 && Some(self.remaining[end]) == self.last {
+                self.remaining.swap(0, end);
+            }
+        }
+
+        self.last = self.remaining.pop();
+        self.last
+    }
+}
+
+thread_local! {
+    /// Object URLs of the sounds that are already in memory, by name. That is built-in sounds which
+    /// were downloaded at startup, and custom sounds.
+    static PRELOADED: RefCell<HashMap<&'static str, String>> = RefCell::default();
+    /// Names of the sounds of all manifests.
+    static MANIFEST_NAMES: RefCell<HashSet<&'static str>> = RefCell::default();
+    static SPRITE: RefCell<Option<Sprite>> = RefCell::default();
+    /// Decoded audio by source URL, for [scheduled](AudioGraph::schedule) playback.
+    static DECODED: RefCell<HashMap<String, AudioBuffer>> = RefCell::default();
+}
+
+/// Slice of a decoded buffer, that makes up a single sound.
+#[derive(Clone)]
+pub struct Clip {
+    buffer: AudioBuffer,
+    /// Start of the sound within the buffer, in seconds.
+    offset: f64,
+    duration: f64,
+}
+
+async fn decode_audio(context: &AudioContext, url: &str) -> Result<AudioBuffer, JsValue> {
+    let data = JsFuture::from(fetch(url).await?.array_buffer()?)
+        .await?
+        .unchecked_into::<ArrayBuffer>();
+    Ok(JsFuture::from(context.decode_audio_data(&data)?)
+        .await?
+        .unchecked_into())
+}
+
+/// Cue sheet of an audio sprite, a single file that contains several sounds one after another.
+#[derive(Deserialize)]
+struct CueSheet {
+    /// File name of the sprite, relative to the `audio` folder.
+    src: String,
+    /// Slices of the sprite, by sound name.
+    cues: HashMap<String, Cue>,
+}
+
+#[derive(Deserialize)]
+struct Cue {
+    /// Offset into the sprite, in seconds.
+    start: f64,
+    duration: f64,
+}
+
+struct Sprite {
+    /// Object URL of the downloaded sprite.
+    url: String,
+    cues: HashMap<String, Cue>,
+}
+
+/// Load the optional sprite described by `audio/sprite.json`, so the sounds it covers play from a
+/// single download. Returns whether a sprite exists.
+pub async fn load_sprite() -> Result<bool, JsValue> {
+    let Some(sheet) = fetch_optional_json::<CueSheet>("audio/sprite.json").await? else {
+        return Ok(false);
+    };
+    let url = fetch_object_url(&fetch(&format!("audio/{}", sheet.src)).await?).await?;
+
+    SPRITE.set(Some(Sprite {
+        url,
+        cues: sheet.cues,
+    }));
+    Ok(true)
+}
+
+/// Fetch and parse a JSON file that doesn't have to exist, in which case it is `None`.
+pub async fn fetch_optional_json<T>(src: &str) -> Result<Option<T>, JsValue>
+where
+    for<'de> T: Deserialize<'de>,
+{
+    // Servers with a single page fallback answer with the index page instead of a 404.
+    let response = fetch(src).await.ok().filter(|response| {
+        response
+            .headers()
+            .get("content-type")
+            .ok()
+            .flatten()
+            .is_some_and(|content_type| content_type.contains("json"))
+    });
+    let Some(response) = response else {
+        return Ok(None);
+    };
+
+    let text = JsFuture::from(response.text()?)
+        .await?
+        .as_string()
+        .unwrap_throw();
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| format!("invalid `{src}`: {e}").into())
+}
+
+/// Duration of the slice that a `#t=<start>,<end>` media fragment selects, in seconds.
+pub fn fragment_duration(src: &str) -> Option<f64> {
+    fragment(src).map(|(start, end)| end - start)
+}
+
+/// Start and end of the slice that a `#t=<start>,<end>` media fragment selects, in seconds.
+fn fragment(src: &str) -> Option<(f64, f64)> {
+    let (_, fragment) = src.rsplit_once("#t=")?;
+    let (start, end) = fragment.split_once(',')?;
+    Some((start.parse().ok()?, end.parse().ok()?))
+}
+
+/// Fetch `src`, treating any response but a success as error.
+pub async fn fetch(src: &str) -> Result<Response, JsValue> {
+    let response = JsFuture::from(window().fetch_with_str(src))
+        .await?
+        .unchecked_into::<Response>();
+    if !response.ok() {
+        return Err(format!("status {} for `{src}`", response.status()).into());
+    }
+
+    Ok(response)
+}
+
+async fn fetch_object_url(response: &Response) -> Result<String, JsValue> {
+    let blob = JsFuture::from(response.blob()?)
+        .await?
+        .unchecked_into::<Blob>();
+    Url::create_object_url_with_blob(&blob)
+}
+
+/// Object URL of a quack that is compiled into the app, so there is always something to play.
+pub fn bundled_quack() -> String {
+    thread_local! {
+        static URL: String = {
+            let bytes = Uint8Array::from(&include_bytes!("../assets/audio/quack1.mp3")[..]);
+            let blob = Blob::new_with_u8_array_sequence_and_options(
+                &Array::of1(&bytes),
+                BlobPropertyBag::new().type_("audio/mpeg"),
+            )
+            .unwrap_throw();
+            Url::create_object_url_with_blob(&blob).unwrap_throw()
+        };
+    }
+
+    URL.with(Clone::clone)
+}
+
+/// MIME type (including codec) to probe browser support for, by file extension.
+pub fn audio_mime(extension: &str) -> Option<&'static str> {
+    Some(match extension {
+        "mp3" => "audio/mpeg",
+        "webm" => "audio/webm; codecs=opus",
+        "ogg" | "opus" => "audio/ogg; codecs=opus",
+        _ => return None,
+    })
+}
+
+/// Web Audio nodes that the sound output is routed through before reaching the speakers.
+pub struct AudioGraph {
+    pub context: AudioContext,
+    /// Listens on the final mix, for visualizations.
+    pub analyser: AnalyserNode,
+    /// Notified whenever a sound starts, or is scheduled to.
+    pub played: Trigger,
+    /// Extra amplification on top of the regular volume, for loud rooms. All voices are mixed into
+    /// this node.
+    boost: GainNode,
+    /// Level of the reverberated signal, silent while the reverb is off.
+    reverb: GainNode,
+    /// Level of the echoes, silent while the echo is off.
+    echo: GainNode,
+    /// Sum of the dry signal and all effects.
+    effects: GainNode,
+    /// Last node in the chain, that keeps layered or sped up sounds from clipping.
+    limiter: DynamicsCompressorNode,
+}
+
+impl AudioGraph {
+    pub fn new() -> Self {
+        let context = AudioContext::new().unwrap_throw();
+        let boost = context.create_gain().unwrap_throw();
+
+        let compressor = context.create_dynamics_compressor().unwrap_throw();
+        compressor.threshold().set_value(-3.0);
+        compressor.knee().set_value(0.0);
+        compressor.ratio().set_value(20.0);
+        compressor.attack().set_value(0.003);
+        compressor.release().set_value(0.25);
+        compressor
+            .connect_with_audio_node(&context.destination())
+            .unwrap_throw();
+
+        let analyser = context.create_analyser().unwrap_throw();
+        analyser.set_fft_size(64);
+        let effects = context.create_gain().unwrap_throw();
+        effects.connect_with_audio_node(&compressor).unwrap_throw();
+        effects.connect_with_audio_node(&analyser).unwrap_throw();
+        boost.connect_with_audio_node(&effects).unwrap_throw();
+
+        let convolver = context.create_convolver().unwrap_throw();
+        convolver.set_buffer(Some(&impulse_response(&context, 1.5)));
+        let reverb = context.create_gain().unwrap_throw();
+        reverb.gain().set_value(0.0);
+        boost
+            .connect_with_audio_node(&convolver)
+            .unwrap_throw()
+            .connect_with_audio_node(&reverb)
+            .unwrap_throw()
+            .connect_with_audio_node(&effects)
+            .unwrap_throw();
+
+        // Each pass through the feedback loop comes back quieter, until the echo fades out.
+        let delay = context.create_delay().unwrap_throw();
+        delay.delay_time().set_value(0.25);
+        let feedback = context.create_gain().unwrap_throw();
+        feedback.gain().set_value(0.4);
+        delay
+            .connect_with_audio_node(&feedback)
+            .unwrap_throw()
+            .connect_with_audio_node(&delay)
+            .unwrap_throw();
+        let echo = context.create_gain().unwrap_throw();
+        echo.gain().set_value(0.0);
+        boost
+            .connect_with_audio_node(&delay)
+            .unwrap_throw()
+            .connect_with_audio_node(&echo)
+            .unwrap_throw()
+            .connect_with_audio_node(&effects)
+            .unwrap_throw();
+
+        Self {
+            context,
+            analyser,
+            boost,
+            reverb,
+            echo,
+            effects,
+            limiter: compressor,
+            played: create_trigger(),
+        }
+    }
+
+    /// Create a new audio element, that is routed through this graph.
+    pub fn voice(&self) -> Voice {
+        let audio = HtmlAudioElement::new().unwrap_throw();
+        let source = self
+            .context
+            .create_media_element_source(&audio)
+            .unwrap_throw();
+        let envelope = self.context.create_gain().unwrap_throw();
+
+        source
+            .connect_with_audio_node(&envelope)
+            .unwrap_throw()
+            .connect_with_audio_node(&self.boost)
+            .unwrap_throw();
+
+        Voice {
+            audio,
+            envelope,
+            started: Cell::new(f64::NEG_INFINITY),
+        }
+    }
+
+    /// Route the output through the limiter, or straight to the speakers.
+    pub fn set_limiter(&self, enabled: bool) {
+        self.effects.disconnect().unwrap_throw();
+        self.effects
+            .connect_with_audio_node(&self.analyser)
+            .unwrap_throw();
+        if enabled {
+            self.effects.connect_with_audio_node(&self.limiter)
+        } else {
+            self.effects
+                .connect_with_audio_node(&self.context.destination())
+        }
+        .unwrap_throw();
+    }
+
+    pub fn set_boost(&self, boost: f64) {
+        self.boost.gain().set_value(boost as f32);
+    }
+
+    pub fn set_reverb(&self, enabled: bool) {
+        self.reverb
+            .gain()
+            .set_value(if enabled { 0.6 } else { 0.0 });
+    }
+
+    pub fn set_echo(&self, enabled: bool) {
+        self.echo.gain().set_value(if enabled { 0.5 } else { 0.0 });
+    }
+
+    /// Schedule the gain of the voice's envelope to follow the `curve`, stretched over the
+    /// `duration` (in seconds) of the sound that just started playing.
+    pub fn apply_curve(&self, voice: &Voice, curve: &[CurvePoint], duration: f64) {
+        let gain = voice.envelope.gain();
+        let now = self.context.current_time();
+
+        voice.started.set(now);
+        gain.cancel_scheduled_values(now).unwrap_throw();
+        follow_curve(&gain, now, curve, duration);
+        self.played.notify();
+    }
+
+    /// Play the `clip` at the context time `when`, for timing that has to be more exact than
+    /// starting an audio element allows. Goes through the same effects as the voices, but isn't
+    /// counted against their limit.
+    pub fn schedule(&self, clip: &Clip, when: f64, rate: f64, volume: f64, curve: &[CurvePoint]) {
+        let source = self.context.create_buffer_source().unwrap_throw();
+        source.set_buffer(Some(&clip.buffer));
+        source.playback_rate().set_value(rate as f32);
+        let envelope = self.context.create_gain().unwrap_throw();
+        let level = self.context.create_gain().unwrap_throw();
+        level.gain().set_value(volume as f32);
+
+        source
+            .connect_with_audio_node(&envelope)
+            .unwrap_throw()
+            .connect_with_audio_node(&level)
+            .unwrap_throw()
+            .connect_with_audio_node(&self.boost)
+            .unwrap_throw();
+
+        let when = when.max(self.context.current_time());
+        follow_curve(&envelope.gain(), when, curve, clip.duration / rate);
+        source
+            .start_with_when_and_grain_offset_and_grain_duration(when, clip.offset, clip.duration)
+            .unwrap_throw();
+        self.played.notify();
+    }
+}
+
+/// Let the `gain` follow the `curve`, stretched over the `duration` (in seconds) of a sound that
+/// starts at the context time `start`.
+fn follow_curve(gain: &AudioParam, start: f64, curve: &[CurvePoint], duration: f64) {
+    if !duration.is_finite() {
+        gain.set_value_at_time(1.0, start).unwrap_throw();
+        return;
+    }
+
+    for (i, point) in curve.iter().enumerate() {
+        let time = start + point.time * duration;
+        if i == 0 {
+            gain.set_value_at_time(point.gain as f32, time)
+        } else {
+            gain.linear_ramp_to_value_at_time(point.gain as f32, time)
+        }
+        .unwrap_throw();
+    }
+}
+
+/// Audio graph that every player of sounds shares. It only exists after the first sound, as
+/// browsers only allow audio contexts after a user gesture.
+pub type SharedGraph = RwSignal<Option<Rc<AudioGraph>>>;
+
+/// The [shared graph](SharedGraph), created right now if nothing played a sound yet.
+pub fn get_or_create_graph(graph: SharedGraph) -> Rc<AudioGraph> {
+    if let Some(graph) = graph.get_untracked() {
+        return graph;
+    }
+
+    let created = Rc::new(AudioGraph::new());
+    graph.set(Some(Rc::clone(&created)));
+    created
+}
+
+/// Wake up the audio context of the `graph`, returning whether it is running afterwards. It
+/// stays suspended if the browser didn't allow playback yet.
+pub async fn resume(graph: &AudioGraph) -> bool {
+    JsFuture::from(graph.context.resume().unwrap_throw())
+        .await
+        .unwrap_throw();
+    graph.context.state() == AudioContextState::Running
+}
+
+/// Stereo noise with an exponential decay over `seconds`, which sounds like a small room when
+/// convolved with.
+fn impulse_response(context: &AudioContext, seconds: f32) -> AudioBuffer {
+    let rate = context.sample_rate();
+    let length = (rate * seconds) as usize;
+    let buffer = context.create_buffer(2, length as u32, rate).unwrap_throw();
+
+    for channel in 0..2 {
+        let samples = (0..length)
+            .map(|i| {
+                let decay = (1.0 - i as f32 / length as f32).powi(3);
+                (fastrand::f32() * 2.0 - 1.0) * decay
+            })
+            .collect::<Vec<_>>();
+        buffer.copy_to_channel(&samples, channel).unwrap_throw();
+    }
+
+    buffer
+}
+
+/// Single audio element of the pool, so several sounds can play on top of each other.
+pub struct Voice {
+    pub audio: HtmlAudioElement,
+    envelope: GainNode,
+    /// Context time at which the current sound started playing.
+    started: Cell<f64>,
+}
+
+impl Voice {
+    pub fn is_idle(&self) -> bool {
+        self.audio.paused() || self.audio.ended()
+    }
+}
+
+/// Pick a voice to play the next sound on. Prefers idle voices, then grows the pool up to `max`
+/// voices, and otherwise cuts off the sound that has been playing the longest.
+pub fn pick_voice(graph: &AudioGraph, voices: &mut Vec<Rc<Voice>>, max: usize) -> Rc<Voice> {
+    let max = max.max(1);
+    let active = &voices[..voices.len().min(max)];
+
+    if let Some(voice) = active.iter().find(|voice| voice.is_idle()) {
+        return Rc::clone(voice);
+    }
+
+    if voices.len() < max {
+        let voice = Rc::new(graph.voice());
+        voices.push(Rc::clone(&voice));
+        return voice;
+    }
+
+    let oldest = active
+        .iter()
+        .min_by(|a, b| a.started.get().total_cmp(&b.started.get()))
+        .unwrap_throw();
+    Rc::clone(oldest)
+}
+
+/// Whether an error is the browser refusing something without permission, like audio that didn't
+/// start from a user gesture or access to the microphone.
+pub fn is_not_allowed(error: &JsValue) -> bool {
+    error
+        .dyn_ref::<DomException>()
+        .is_some_and(|e| e.name() == "NotAllowedError")
+}
+
+/// Single control point of a gain-over-time curve, both values normalized to `0.0..=1.0`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CurvePoint {
+    pub time: f64,
+    pub gain: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: Sound = Sound {
+        name: "a",
+        extensions: &["mp3"],
+    };
+    const B: Sound = Sound {
+        name: "b",
+        extensions: &["mp3"],
+    };
+    const C: Sound = Sound {
+        name: "c",
+        extensions: &["mp3"],
+    };
+
+    #[test]
+    fn shuffle_bag_draws_every_sound_once() {
+        let pool = [A, B, C];
+        let mut bag = ShuffleBag::default();
+
+        let mut drawn = (0..3).map(|_| bag.draw(&pool).unwrap()).collect::<Vec<_>>();
+        drawn.sort_by_key(|sound| sound.name);
+        assert_eq!(drawn, pool);
+    }
+
+    #[test]
+    fn shuffle_bag_avoids_repeats_across_refills() {
+        let pool = [A, B, C];
+        let mut bag = ShuffleBag::default();
+
+        let mut last = bag.draw(&pool);
+        for _ in 0..100 {
+            let next = bag.draw(&pool);
+            assert_ne!(next, last);
+            last = next;
+        }
+    }
+
+    #[test]
+    fn shuffle_bag_skips_removed_sounds() {
+        let mut bag = ShuffleBag::default();
+        bag.draw(&[A, B, C]);
+
+        for _ in 0..10 {
+            assert_eq!(bag.draw(&[C]), Some(C));
+        }
+    }
+
+    #[test]
+    fn shuffle_bag_with_empty_pool() {
+        assert_eq!(ShuffleBag::default().draw(&[]), None);
+    }
+
+    #[test]
+    fn fragment_duration_of_slice() {
+        assert_eq!(fragment_duration("blob:sprite#t=1.5,2.25"), Some(0.75));
+        assert_eq!(fragment_duration("audio/quack1.mp3"), None);
+        assert_eq!(fragment_duration("blob:sprite#t=1.5"), None);
+        assert_eq!(fragment_duration("blob:sprite#t=a,b"), None);
+    }
+
+    #[test]
+    fn weighted_pool_repeats_by_weight() {
+        let weights = SoundWeights::from([("a".to_owned(), 3)]);
+        let favorites = FavoriteSounds::from(["b".to_owned()]);
+
+        let pool = weighted_pool(&[A, B, C], &weights, &favorites);
+        let count = |sound| pool.iter().filter(|&&entry| entry == sound).count();
+        assert_eq!(count(A), 3);
+        assert_eq!(count(B), FAVORITE_BOOST);
+        assert_eq!(count(C), 1);
+    }
+
+    #[test]
+    fn weighted_pool_limits_stored_weights() {
+        let weights = SoundWeights::from([("a".to_owned(), u8::MAX)]);
+        let favorites = FavoriteSounds::from(["a".to_owned()]);
+
+        let pool = weighted_pool(&[A], &weights, &favorites);
+        assert_eq!(pool.len(), usize::from(MAX_WEIGHT) * FAVORITE_BOOST);
+    }
+
+    #[test]
+    fn weighted_pool_leaves_out_never() {
+        let weights = SoundWeights::from([("a".to_owned(), 0)]);
+        assert_eq!(
+            weighted_pool(&[A, B], &weights, &FavoriteSounds::new()),
+            [B]
+        );
+    }
+
+    #[test]
+    fn weighted_pool_keeps_all_when_all_never() {
+        let weights = SoundWeights::from([("a".to_owned(), 0), ("b".to_owned(), 0)]);
+        assert_eq!(
+            weighted_pool(&[A, B], &weights, &FavoriteSounds::new()),
+            [A, B]
+        );
+    }
+
+    #[test]
+    fn enabled_sounds_leaves_out_disabled() {
+        let disabled = DisabledSounds::from(["b".to_owned()]);
+        assert_eq!(enabled_sounds(&[A, B, C], &disabled), [A, C]);
+    }
+
+    #[test]
+    fn enabled_sounds_keeps_all_when_all_disabled() {
+        let disabled = DisabledSounds::from(["a".to_owned(), "b".to_owned()]);
+        assert_eq!(enabled_sounds(&[A, B], &disabled), [A, B]);
+    }
+}
@@ -0,0 +1,56 @@
+/// Shown in the navigation bar and the page title. Set with `QUACK_APP_NAME`, like the rest of the
+/// branding, so companies can build an internal instance under their own name. Which ducks and
+/// sounds exist is up to the [asset manifest](crate::assets) instead.
+pub const APP_NAME: &str = match option_env!("QUACK_APP_NAME") {
+    Some(name) => name,
+    None => "Quack",
+};
+
+/// URL of an image that replaces the duck emoji in front of the app name. Set with `QUACK_LOGO`.
+pub const LOGO: Option<&str> = option_env!("QUACK_LOGO");
+
+/// ID of the duck that is selected until the user picks another one, instead of the first one of
+/// the manifest. Set with `QUACK_DEFAULT_DUCK`.
+pub const DEFAULT_DUCK: Option<&str> = option_env!("QUACK_DEFAULT_DUCK");
+
+/// Links in the footer, as `<label>=<url>` pairs separated by semicolons. Set with
+/// `QUACK_FOOTER_LINKS`.
+const FOOTER_LINKS: &str = match option_env!("QUACK_FOOTER_LINKS") {
+    Some(links) => links,
+    None => "Source on GitHub=https://github.com/dnaka91/quack",
+};
+
+/// Labels and URLs of the [footer links](FOOTER_LINKS), skipping malformed entries.
+pub fn footer_links() -> impl Iterator<Item = (&'static str, &'static str)> {
+    FOOTER_LINKS
+        .split(';')
+        .filter_map(|link| link.split_once('='))
+        .map(|(label, url)| (label.trim(), url.trim()))
+}
+
+/// Shown below the navigation bar, above the duck. Set with `QUACK_TAGLINE`.
+pub const TAGLINE: &str = match option_env!("QUACK_TAGLINE") {
+    Some(tagline) => tagline,
+    None => "Rubber Ducking as a service! Finally!",
+};
+
+/// Credits for the assets in the licenses dialog, as `<text>=<name>=<url>` triples separated by
+/// semicolons. Set with `QUACK_ASSET_CREDITS`, for instances that bring their own ducks and sounds.
+const ASSET_CREDITS: &str = match option_env!("QUACK_ASSET_CREDITS") {
+    Some(credits) => credits,
+    None => concat!(
+        "Images from=Pexels=https://www.pexels.com/search/rubber%20duck/;",
+        "Most sounds from=Videvo=",
+        "https://www.videvo.net/search/?q=animal+duck+cartoon&mode=sound-effects;",
+        "Individual sounds by=Albert Larsan=https://albertlarsan.fr/",
+    ),
+};
+
+/// Texts, names and URLs of the [asset credits](ASSET_CREDITS), skipping malformed entries.
+pub fn asset_credits() -> impl Iterator<Item = (&'static str, &'static str, &'static str)> {
+    ASSET_CREDITS.split(';').filter_map(|credit| {
+        let (text, rest) = credit.split_once('=')?;
+        let (name, url) = rest.split_once('=')?;
+        Some((text.trim(), name.trim(), url.trim()))
+    })
+}
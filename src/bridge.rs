@@ -0,0 +1,99 @@
+use std::{rc::Rc, time::Duration};
+
+use leptos::{create_effect, prelude::*, set_timeout, store_value, StoredValue};
+use log::debug;
+use wasm_bindgen::{closure::Closure, JsCast, UnwrapThrowExt};
+use web_sys::{CloseEvent, MessageEvent, WebSocket};
+
+use crate::diagnostics::report_error;
+
+/// Socket of the bridge, together with its callbacks that must live as long as the socket.
+struct BridgeConnection {
+    socket: WebSocket,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut(CloseEvent)>,
+}
+
+/// Wait before connecting again after a lost connection.
+const RETRY: Duration = Duration::from_secs(5);
+
+/// Keep a connection to `ws://localhost:<port>` open while `enabled`, calling `on_message` for
+/// every message received. This lets local tools like a shell hook after `cargo build` make the
+/// duck quack. Lost connections are retried every few seconds.
+pub fn create_bridge(enabled: Signal<bool>, port: Signal<u16>, on_message: impl Fn() + 'static) {
+    let connection = store_value(None::<BridgeConnection>);
+    // Bumped on every change of the settings, so a pending retry notices that it is stale.
+    let run = store_value(0_u32);
+    let on_message = Rc::new(on_message) as Rc<dyn Fn()>;
+
+    create_effect(move |_| {
+        let url = enabled
+            .get()
+            .then(|| format!("ws://localhost:{}", port.get()));
+
+        run.update_value(|run| *run += 1);
+        if let Some(old) = connection.with_value(|c| c.as_ref().map(|c| c.socket.clone())) {
+            // Detached first, as the callbacks are dropped together with the connection, before
+            // the socket fires its close event.
+            old.set_onmessage(None);
+            old.set_onclose(None);
+            connection.set_value(None);
+            old.close().unwrap_throw();
+        }
+
+        if let Some(url) = url {
+            connect(url.into(), connection, run, Rc::clone(&on_message));
+        }
+    });
+}
+
+/// Open the bridge socket to `url`, that reconnects by itself whenever it closes, as long as the
+/// `run` stays the same.
+fn connect(
+    url: Rc<str>,
+    connection: StoredValue<Option<BridgeConnection>>,
+    run: StoredValue<u32>,
+    on_message: Rc<dyn Fn()>,
+) {
+    let this_run = run.get_value();
+    let socket = match WebSocket::new(&url) {
+        Ok(socket) => socket,
+        Err(e) => {
+            report_error(
+                "bridge",
+                format!("failed connecting the bridge to `{url}`:\n{e:?}"),
+            );
+            return;
+        }
+    };
+
+    let message = Closure::<dyn FnMut(_)>::new({
+        let on_message = Rc::clone(&on_message);
+        move |_: MessageEvent| on_message()
+    });
+    let close = Closure::<dyn FnMut(_)>::new({
+        let url = Rc::clone(&url);
+        move |_: CloseEvent| {
+            debug!("bridge connection to `{url}` closed, retrying in {RETRY:?}");
+            let (url, on_message) = (Rc::clone(&url), Rc::clone(&on_message));
+            set_timeout(
+                move || {
+                    // The bridge may have been turned off or moved to another port meanwhile.
+                    if run.get_value() == this_run {
+                        connect(url, connection, run, on_message);
+                    }
+                },
+                RETRY,
+            );
+        }
+    });
+
+    socket.set_onmessage(Some(message.as_ref().unchecked_ref()));
+    socket.set_onclose(Some(close.as_ref().unchecked_ref()));
+
+    connection.set_value(Some(BridgeConnection {
+        socket,
+        _on_message: message,
+        _on_close: close,
+    }));
+}
@@ -0,0 +1,60 @@
+use js_sys::Reflect;
+use leptos::window;
+use serde::Serialize;
+
+/// Browser APIs that features of the app depend on. They are probed once, so features without
+/// their API can be turned off up front, instead of failing when they are used.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Capabilities {
+    pub web_audio: bool,
+    /// Local storage, that may exist and still refuse writes, like in private windows of some
+    /// browsers.
+    pub storage: bool,
+    pub indexed_db: bool,
+    pub speech: bool,
+    pub speech_recognition: bool,
+    pub recording: bool,
+    pub microphone: bool,
+    pub websocket: bool,
+    pub clipboard: bool,
+}
+
+impl Capabilities {
+    pub fn get() -> Self {
+        thread_local! {
+            static CAPABILITIES: Capabilities = Capabilities::probe();
+        }
+
+        CAPABILITIES.with(|capabilities| *capabilities)
+    }
+
+    fn probe() -> Self {
+        let window = window();
+        let has = |target: &_, name: &str| Reflect::has(target, &name.into()).unwrap_or_default();
+
+        Self {
+            web_audio: has(&window, "AudioContext"),
+            storage: window
+                .local_storage()
+                .ok()
+                .flatten()
+                .is_some_and(|storage| {
+                    storage.set_item("capabilities", "").is_ok()
+                        && storage.remove_item("capabilities").is_ok()
+                }),
+            indexed_db: window.indexed_db().is_ok_and(|factory| factory.is_some()),
+            speech: has(&window, "speechSynthesis"),
+            speech_recognition: has(&window, "SpeechRecognition")
+                || has(&window, "webkitSpeechRecognition"),
+            recording: has(&window, "MediaRecorder"),
+            microphone: has(&window.navigator(), "mediaDevices"),
+            websocket: has(&window, "WebSocket"),
+            clipboard: has(&window.navigator(), "clipboard"),
+        }
+    }
+}
+
+/// Tooltip for a feature that can't be used, because the browser lacks `api`.
+pub fn unsupported(available: bool, api: &str) -> Option<String> {
+    (!available).then(|| format!("Not available, as this browser doesn't support {api}"))
+}
@@ -0,0 +1,41 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use leptos::on_cleanup;
+
+/// Something that can be run from the command palette.
+#[derive(Clone)]
+pub struct Command {
+    id: u32,
+    pub label: String,
+    pub run: Rc<dyn Fn()>,
+}
+
+thread_local! {
+    static COMMANDS: RefCell<Vec<Command>> = RefCell::default();
+    static NEXT_ID: Cell<u32> = Cell::default();
+}
+
+/// Offer `run` in the command palette, for as long as the current component lives.
+pub fn register_command(label: impl Into<String>, run: impl Fn() + 'static) {
+    let id = NEXT_ID.get() + 1;
+    NEXT_ID.set(id);
+    COMMANDS.with_borrow_mut(|commands| {
+        commands.push(Command {
+            id,
+            label: label.into(),
+            run: Rc::new(run),
+        });
+    });
+
+    on_cleanup(move || {
+        COMMANDS.with_borrow_mut(|commands| commands.retain(|command| command.id != id));
+    });
+}
+
+/// All currently registered commands, in order of registration.
+pub fn commands() -> Vec<Command> {
+    COMMANDS.with_borrow(Clone::clone)
+}
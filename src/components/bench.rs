@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use js_sys::ArrayBuffer;
+use leptos::{component, prelude::*, spawn_local, view, CollectView, IntoView};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+use super::settings::DEFAULT_INTENSITY_CURVE;
+use crate::{
+    audio::{builtin_sounds, fetch, pick_voice, resume, AudioGraph},
+    diagnostics::report_error,
+    time::{now, sleep},
+};
+
+/// Hidden page, opened with a `?diagnostics` query parameter, that measures how fast the sound
+/// engine reacts on this machine. Helps with "the quack lags" reports.
+#[component]
+pub fn bench() -> impl IntoView {
+    let results = create_rw_signal(Vec::<(String, String)>::new());
+    let running = create_rw_signal(false);
+
+    let run = move |_| {
+        // Taken right away, so the latency includes everything after the click.
+        let clicked = now();
+        running.set(true);
+        results.set(Vec::new());
+
+        spawn_local(async move {
+            if let Err(e) = measure(clicked, results).await {
+                report_error("bench", format!("benchmark failed:\n{e:?}"));
+                results.update(|results| results.push(("Error".to_owned(), format!("{e:?}"))));
+            }
+            running.set(false);
+        });
+    };
+
+    view! {
+        <div class="flex flex-col gap-3 items-center p-4">
+            <h1 class="text-2xl">"🦆 Sound engine diagnostics"</h1>
+            <button class="btn p-2" on:click=run disabled=running>
+                {move || if running.get() { "Measuring…" } else { "Run measurements" }}
+            </button>
+            <table class="text-left">
+                <tbody>
+                    {move || {
+                        results
+                            .get()
+                            .into_iter()
+                            .map(|(name, value)| {
+                                view! {
+                                    <tr>
+                                        <th class="pr-4 font-normal text-slate-400">{name}</th>
+                                        <td>
+                                            <code>{value}</code>
+                                        </td>
+                                    </tr>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </tbody>
+            </table>
+            <a class="link" href="?">
+                "Back to the duck"
+            </a>
+        </div>
+    }
+}
+
+async fn measure(clicked: f64, results: RwSignal<Vec<(String, String)>>) -> Result<(), JsValue> {
+    /// Requested delay between timer callbacks, in milliseconds.
+    const TIMER_INTERVAL: u32 = 10;
+    const TIMER_SAMPLES: usize = 20;
+
+    let push = |name: &str, value: String| {
+        results.update(|results| results.push((name.to_owned(), value)));
+    };
+
+    // Playback first, while the click still counts as user gesture. It goes through the same
+    // audio graph and voice pool as the play button, so it measures everything a quack waits for.
+    let graph = AudioGraph::new();
+    if !resume(&graph).await {
+        return Err("the browser kept the audio context suspended".into());
+    }
+    let voice = pick_voice(&graph, &mut Vec::new(), 1);
+    let audio = &voice.audio;
+    audio.set_volume(0.1);
+    let src = builtin_sounds()[0]
+        .sources(audio)
+        .next()
+        .ok_or("no playable sound format")?;
+    audio.set_src(&src);
+    JsFuture::from(audio.play()?).await?;
+    graph.apply_curve(&voice, DEFAULT_INTENSITY_CURVE, audio.duration());
+    push("Click to playback", format!("{:.1} ms", now() - clicked));
+
+    let context = &graph.context;
+    for property in ["baseLatency", "outputLatency"] {
+        let latency = js_sys::Reflect::get(context, &property.into())?;
+        push(
+            property,
+            latency.as_f64().map_or_else(
+                || "unsupported".to_owned(),
+                |latency| format!("{:.1} ms", latency * 1000.0),
+            ),
+        );
+    }
+
+    for sound in builtin_sounds() {
+        let Some(src) = sound.sources(audio).next() else {
+            continue;
+        };
+        let data = JsFuture::from(fetch(&src).await?.array_buffer()?)
+            .await?
+            .unchecked_into::<ArrayBuffer>();
+
+        let start = now();
+        JsFuture::from(context.decode_audio_data(&data)?).await?;
+        push(
+            &format!("Decode {}", sound.name),
+            format!("{:.1} ms", now() - start),
+        );
+    }
+
+    let mut deviations = Vec::with_capacity(TIMER_SAMPLES);
+    for _ in 0..TIMER_SAMPLES {
+        let start = now();
+        sleep(Duration::from_millis(TIMER_INTERVAL.into())).await;
+        deviations.push(now() - start - f64::from(TIMER_INTERVAL));
+    }
+    let mean = deviations.iter().sum::<f64>() / deviations.len() as f64;
+    let max = deviations.iter().copied().fold(0.0, f64::max);
+    push(
+        "Timer jitter",
+        format!("{mean:.1} ms mean, {max:.1} ms max"),
+    );
+
+    JsFuture::from(context.close()?).await?;
+    Ok(())
+}
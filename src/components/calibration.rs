@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use leptos::{component, prelude::*, spawn_local, store_value, view, IntoView};
+
+use super::inputs::Slider;
+use crate::{
+    audio::Sound,
+    time::{now, sleep},
+};
+
+/// Largest latency that can be set, in milliseconds. Anything above is more likely a missed tap
+/// than a slow device.
+pub const MAX_LATENCY: f64 = 500.0;
+
+/// Measures the delay between playing a sound and hearing it, by letting the user tap along to a
+/// steady beat. Taps land late by about as much as the sound does.
+#[component]
+pub fn latency_calibration(
+    latency: RwSignal<f64>,
+    default: f64,
+    /// Sounds to play the beat with, of which the first one is used.
+    #[prop(into)]
+    sounds: Signal<Vec<Sound>>,
+    #[prop(into)] quack: SignalSetter<Option<Sound>>,
+) -> impl IntoView {
+    const BEATS: usize = 8;
+    const INTERVAL: Duration = Duration::from_millis(750);
+
+    let running = create_rw_signal(false);
+    let result = create_rw_signal(None::<String>);
+    let beats = store_value(Vec::<f64>::new());
+    let taps = store_value(Vec::<f64>::new());
+
+    let finish = move || {
+        running.set(false);
+        let offsets = taps.with_value(|taps| {
+            beats.with_value(|beats| {
+                let mut offsets = taps
+                    .iter()
+                    .filter_map(|tap| {
+                        beats
+                            .iter()
+                            .map(|beat| tap - beat)
+                            .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+                    })
+                    .collect::<Vec<_>>();
+                offsets.sort_by(f64::total_cmp);
+                offsets
+            })
+        });
+
+        if offsets.len() < BEATS / 2 {
+            result.set(Some("Not enough taps, try again.".to_owned()));
+            return;
+        }
+        // The median, so a single stray tap doesn't throw off the result.
+        let measured = offsets[offsets.len() / 2].clamp(0.0, MAX_LATENCY).round();
+        latency.set(measured);
+        result.set(Some(format!("Measured {measured:.0} ms.")));
+    };
+
+    let start = move |_| {
+        let Some(sound) = sounds.with_untracked(|sounds| sounds.first().copied()) else {
+            return;
+        };
+        beats.set_value(Vec::new());
+        taps.set_value(Vec::new());
+        result.set(None);
+        running.set(true);
+
+        spawn_local(async move {
+            // Like the sequencer, every beat is scheduled relative to the start.
+            let start = now();
+            for beat in 0..BEATS {
+                let at = start + beat as f64 * INTERVAL.as_secs_f64() * 1000.0;
+                sleep(Duration::from_secs_f64((at - now()).max(0.0) / 1000.0)).await;
+                quack.set(Some(sound));
+                beats.update_value(|beats| beats.push(now()));
+            }
+            // Room for the last tap.
+            sleep(INTERVAL).await;
+            finish();
+        });
+    };
+    let tap = move |_| {
+        if running.get_untracked() {
+            taps.update_value(|taps| taps.push(now()));
+        }
+    };
+
+    view! {
+        <Slider label="Latency (ms)" value=latency default=default min=0.0 max=MAX_LATENCY/>
+        <div class="slider">
+            <button class="btn py-0.5 px-1" disabled=running on:click=start>
+                "Calibrate"
+            </button>
+            <button class="btn grow py-2" disabled=move || !running.get() on:pointerdown=tap>
+                "Tap along"
+            </button>
+        </div>
+        <p class="settings-hint" aria-live="polite">
+            {move || {
+                if running.get() {
+                    "Tap the button right when you hear each quack.".to_owned()
+                } else {
+                    result.get().unwrap_or_default()
+                }
+            }}
+        </p>
+    }
+}
@@ -0,0 +1,207 @@
+use std::{cell::RefCell, f64::consts::TAU, rc::Rc, time::Duration};
+
+use js_sys::Array;
+use leptos::{
+    component, document, prelude::*, request_animation_frame, set_timeout, view, IntoView,
+};
+use wasm_bindgen::{closure::Closure, JsCast, UnwrapThrowExt};
+use web_sys::{
+    AnalyserNode, Blob, BlobEvent, BlobPropertyBag, CanvasRenderingContext2d, HtmlCanvasElement,
+    HtmlElement, HtmlImageElement, MediaRecorder, MediaRecorderOptions, Url,
+};
+
+use crate::{
+    audio::Sound, capabilities::Capabilities, commands::register_command, ducks::Duck, time::now,
+};
+
+/// Button that plays a quack and records a short clip of the duck bouncing along to it, as video
+/// to paste into reviews when the duck solved it. Recorded as WebM where the
+/// browser supports it.
+#[component]
+pub fn clip_button(
+    #[prop(into)] duck: Signal<Duck>,
+    #[prop(into)] analyser: Signal<Option<AnalyserNode>>,
+    #[prop(into)] quack: SignalSetter<Option<Sound>>,
+) -> impl IntoView {
+    let recording = create_rw_signal(false);
+    let capabilities = Capabilities::get();
+    let supported = capabilities.recording && capabilities.web_audio;
+
+    let record = move || {
+        if recording.get_untracked() {
+            return;
+        }
+        recording.set(true);
+        quack.set(None);
+        record_clip(duck.get_untracked(), analyser, move || recording.set(false));
+    };
+    if supported {
+        register_command("Save a clip", record);
+    }
+
+    view! {
+        <button
+            class="btn self-center py-1 px-2 mt-2 text-sm"
+            class:hidden=!supported
+            disabled=recording
+            on:click=move |_| record()
+        >
+            {move || if recording.get() { "🎬 Recording…" } else { "🎬 Save a clip" }}
+        </button>
+    }
+}
+
+fn record_clip(duck: Duck, analyser: Signal<Option<AnalyserNode>>, done: impl FnOnce() + 'static) {
+    const WIDTH: f64 = 400.0;
+    const HEIGHT: f64 = 300.0;
+    const IMAGE_HEIGHT: f64 = 200.0;
+    const BOUNCE: f64 = 24.0;
+    /// Length of the clip in milliseconds.
+    const DURATION: f64 = 2500.0;
+
+    let image = HtmlImageElement::new().unwrap_throw();
+    // The lowest density is plenty for a clip this small.
+    image.set_src(duck.src());
+
+    let canvas = document()
+        .create_element("canvas")
+        .unwrap_throw()
+        .unchecked_into::<HtmlCanvasElement>();
+    canvas.set_width(WIDTH as u32);
+    canvas.set_height(HEIGHT as u32);
+    let ctx = canvas
+        .get_context("2d")
+        .unwrap_throw()
+        .unwrap_throw()
+        .unchecked_into::<CanvasRenderingContext2d>();
+
+    let stream = canvas
+        .capture_stream_with_frame_request_rate(30.0)
+        .unwrap_throw();
+    let recorder = if MediaRecorder::is_type_supported("video/webm") {
+        MediaRecorder::new_with_media_stream_and_media_recorder_options(
+            &stream,
+            MediaRecorderOptions::new().mime_type("video/webm"),
+        )
+    } else {
+        MediaRecorder::new_with_media_stream(&stream)
+    }
+    .unwrap_throw();
+
+    let chunks = Rc::new(RefCell::new(Vec::<Blob>::new()));
+    let data_available = Closure::<dyn FnMut(BlobEvent)>::new({
+        let chunks = Rc::clone(&chunks);
+        move |event: BlobEvent| chunks.borrow_mut().extend(event.data())
+    });
+    recorder.set_ondataavailable(Some(data_available.as_ref().unchecked_ref()));
+
+    let stop = Closure::once_into_js({
+        let recorder = recorder.clone();
+        move || {
+            drop(data_available);
+            let parts = chunks.borrow().iter().collect::<Array>();
+            let blob = Blob::new_with_blob_sequence_and_options(
+                &parts,
+                BlobPropertyBag::new().type_(&recorder.mime_type()),
+            )
+            .unwrap_throw();
+            download(
+                &blob,
+                &format!("quack.{}", extension(&recorder.mime_type())),
+            );
+            done();
+        }
+    });
+    recorder.set_onstop(Some(stop.unchecked_ref()));
+    recorder.start().unwrap_throw();
+
+    let start = now();
+    fn frame(draw: Rc<dyn Fn() -> bool>) {
+        request_animation_frame(move || {
+            if draw() {
+                frame(draw);
+            }
+        });
+    }
+    frame(Rc::new(move || {
+        let elapsed = now() - start;
+        if elapsed >= DURATION {
+            recorder.stop().unwrap_throw();
+            return false;
+        }
+
+        ctx.set_fill_style(&"#1e293b".into());
+        ctx.fill_rect(0.0, 0.0, WIDTH, HEIGHT);
+
+        let offset = (elapsed / 1000.0 * 1.5 * TAU).sin().abs() * BOUNCE;
+        if image.complete() && image.natural_height() > 0 {
+            let width =
+                IMAGE_HEIGHT * f64::from(image.natural_width()) / f64::from(image.natural_height());
+            ctx.draw_image_with_html_image_element_and_dw_and_dh(
+                &image,
+                (WIDTH - width) / 2.0,
+                BOUNCE - offset,
+                width,
+                IMAGE_HEIGHT,
+            )
+            .unwrap_throw();
+        }
+
+        if let Some(analyser) = analyser.get_untracked() {
+            let mut data = vec![0; analyser.frequency_bin_count() as usize];
+            analyser.get_byte_frequency_data(&mut data);
+
+            ctx.set_fill_style(&"#0ea5e9".into());
+            let width = WIDTH / data.len() as f64;
+            for (i, value) in data.iter().enumerate() {
+                let height = f64::from(*value) / 255.0 * (HEIGHT - IMAGE_HEIGHT - BOUNCE);
+                ctx.fill_rect(i as f64 * width, HEIGHT - height, width - 1.0, height);
+            }
+        }
+
+        true
+    }));
+}
+
+/// Let the browser save the `blob` as a file.
+fn download(blob: &Blob, name: &str) {
+    let url = Url::create_object_url_with_blob(blob).unwrap_throw();
+    let link = document()
+        .create_element("a")
+        .unwrap_throw()
+        .unchecked_into::<HtmlElement>();
+    link.set_attribute("href", &url).unwrap_throw();
+    link.set_attribute("download", name).unwrap_throw();
+    link.click();
+    // Some browsers only start reading the URL after the click, and abort the download if it is
+    // already gone.
+    set_timeout(
+        move || {
+            Url::revoke_object_url(&url).ok();
+        },
+        Duration::from_secs(1),
+    );
+}
+
+/// File extension for a recording of the given MIME type, as browsers without WebM support pick
+/// their own format, like MP4 for Safari.
+fn extension(mime_type: &str) -> &'static str {
+    match mime_type.split(';').next().unwrap_or_default().trim() {
+        "video/mp4" => "mp4",
+        "video/x-matroska" => "mkv",
+        _ => "webm",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_of_mime_type() {
+        assert_eq!(extension("video/webm"), "webm");
+        assert_eq!(extension("video/webm;codecs=vp8"), "webm");
+        assert_eq!(extension("video/mp4; codecs=\"avc1.42E01E\""), "mp4");
+        assert_eq!(extension(""), "webm");
+    }
+}
@@ -0,0 +1,269 @@
+use leptos::{component, create_effect, prelude::*, view, IntoView, Show};
+use web_sys::PointerEvent;
+
+use super::{
+    clip::ClipButton, sequencer::Sequencer, sounds::Sounds, talk::Talk, trainer::KeyTrainer,
+    transcript::Transcript,
+};
+use crate::{
+    audio::{CurvePoint, DisabledSounds, FavoriteSounds, SharedGraph, Sound, SoundWeights},
+    branding::TAGLINE,
+    capabilities::{unsupported, Capabilities},
+    commands::register_command,
+    diagnostics::report_error,
+    ducks::{prefetch_image, Duck},
+    transcription::SavedTranscript,
+};
+
+#[component]
+pub fn content(
+    ducky: RwSignal<Duck>,
+    #[prop(into)] playback_rate: Signal<f64>,
+    #[prop(into)] volume: Signal<f64>,
+    #[prop(into)] intensity_curve: Signal<Vec<CurvePoint>>,
+    #[prop(into)] limiter: Signal<bool>,
+    #[prop(into)] boost: Signal<f64>,
+    #[prop(into)] max_voices: Signal<u8>,
+    #[prop(into)] pitch_variation: Signal<f64>,
+    #[prop(into)] reverb: Signal<bool>,
+    #[prop(into)] echo: Signal<bool>,
+    /// Skip anything that is only nice to have, to save energy.
+    #[prop(into)]
+    saving_power: Signal<bool>,
+    /// Hold back sounds that play by themselves.
+    #[prop(into)]
+    in_meeting: Signal<bool>,
+    #[prop(into)] loop_gap: Signal<f64>,
+    #[prop(into)] play_key: Signal<Option<String>>,
+    #[prop(into)] sound_weights: Signal<SoundWeights>,
+    #[prop(into)] disabled_sounds: Signal<DisabledSounds>,
+    #[prop(into)] favorite_sounds: Signal<FavoriteSounds>,
+    #[prop(into)] pack_sounds: Signal<Vec<Sound>>,
+    #[prop(into)] sound_pack: Signal<String>,
+    #[prop(into)] custom_sounds: Signal<Vec<Sound>>,
+    #[prop(into)] speech_voice: Signal<String>,
+    #[prop(into)] speech_pitch: Signal<f64>,
+    #[prop(into)] speech_rate: Signal<f64>,
+    transcripts: RwSignal<Vec<SavedTranscript>>,
+    #[prop(into)] latency: Signal<f64>,
+    /// Loudness of the microphone, for the duck to bob along to.
+    #[prop(into)]
+    microphone_level: Signal<f64>,
+    quack: RwSignal<Option<Sound>>,
+    /// Only keep the duck and its play button, without any of the extra tools.
+    classroom: bool,
+) -> impl IntoView {
+    /// Minimum horizontal distance in pixels, for a swipe to switch the duck. Anything shorter is
+    /// a tap, that quacks.
+    const SWIPE_DISTANCE: i32 = 50;
+    /// Factor from the microphone level to how far the duck bobs, so a normal speaking voice
+    /// already moves it the whole way.
+    const BOB_GAIN: f64 = 10.0;
+
+    let graph: SharedGraph = create_rw_signal(None);
+    let analyser = Signal::derive(move || {
+        graph.with(|graph| graph.as_ref().map(|graph| graph.analyser.clone()))
+    });
+    let trainer = create_rw_signal(false);
+    let talk = create_rw_signal(false);
+    let transcript = create_rw_signal(false);
+    let swipe_start = create_rw_signal(None::<i32>);
+    let squished = create_rw_signal(false);
+    let previous = move || ducky.update(|duck| *duck = duck.previous());
+    let next = move || ducky.update(|duck| *duck = duck.next());
+
+    if !classroom {
+        register_command("Open or close the key trainer", move || {
+            trainer.update(|show| *show = !*show);
+        });
+        register_command("Talk to the duck", move || talk.set(true));
+        register_command("Transcribe what you say", move || transcript.set(true));
+    }
+
+    let swipe_end = move |event: PointerEvent| {
+        let Some(start) = swipe_start.get_untracked() else {
+            return;
+        };
+        swipe_start.set(None);
+
+        match event.client_x() - start {
+            distance if distance >= SWIPE_DISTANCE => previous(),
+            distance if distance <= -SWIPE_DISTANCE => next(),
+            _ => {
+                // Goes through the same request as every other way of playing a sound.
+                quack.set(None);
+                squished.set(true);
+            }
+        }
+    };
+
+    view! {
+        <div class="flex flex-col justify-center text-center">
+            <h1 class="text-xl italic">{TAGLINE}</h1>
+            <div class="flex gap-2 items-center my-8">
+                <button
+                    class="btn p-2"
+                    aria-label="Previous duck"
+                    on:pointerenter=move |_| {
+                        prefetch_image(ducky.get_untracked().previous().preferred_srcset())
+                    }
+                    on:click=move |_| previous()
+                >
+                    "◀"
+                </button>
+                <DuckImage
+                    duck=ducky
+                    alt=Signal::derive(move || ducky.get().description().to_owned())
+                    class="min-w-0 rounded-xl cursor-pointer max-w-[400px] touch-pan-y"
+                    squished=squished
+                    bob=Signal::derive(move || (microphone_level.get() * BOB_GAIN).min(1.0))
+                    on:animationend=move |_| squished.set(false)
+                    on:pointerdown=move |event| swipe_start.set(Some(event.client_x()))
+                    on:pointerup=swipe_end
+                    on:pointercancel=move |_| swipe_start.set(None)
+                />
+                <button
+                    class="btn p-2"
+                    aria-label="Next duck"
+                    on:pointerenter=move |_| {
+                        prefetch_image(ducky.get_untracked().next().preferred_srcset())
+                    }
+                    on:click=move |_| next()
+                >
+                    "▶"
+                </button>
+            </div>
+
+            <Sounds
+                playback_rate=playback_rate
+                volume=volume
+                intensity_curve=intensity_curve
+                limiter=limiter
+                boost=boost
+                max_voices=max_voices
+                pitch_variation=pitch_variation
+                reverb=reverb
+                echo=echo
+                saving_power=saving_power
+                in_meeting=in_meeting
+                loop_gap=loop_gap
+                play_key=play_key
+                sound_weights=sound_weights
+                disabled_sounds=disabled_sounds
+                favorite_sounds=favorite_sounds
+                pack_sounds=pack_sounds
+                duck=ducky.into()
+                custom_sounds=custom_sounds
+                graph=graph
+                quack=quack
+                classroom=classroom
+            />
+            {(!classroom)
+                .then(|| {
+                    view! {
+                        <div class="flex gap-2 justify-center">
+                            <ClipButton duck=ducky analyser=analyser quack=quack/>
+                            <button
+                                class="btn self-center py-1 px-2 mt-2 text-sm"
+                                aria-expanded=move || trainer.get().to_string()
+                                on:click=move |_| trainer.update(|show| *show = !*show)
+                            >
+                                "⌨️ Key trainer"
+                            </button>
+                            <button
+                                class="btn self-center py-1 px-2 mt-2 text-sm"
+                                aria-expanded=move || talk.get().to_string()
+                                disabled=!Capabilities::get().speech
+                                title=unsupported(Capabilities::get().speech, "speech synthesis")
+                                on:click=move |_| talk.update(|show| *show = !*show)
+                            >
+                                "💬 Talk"
+                            </button>
+                            <button
+                                class="btn self-center py-1 px-2 mt-2 text-sm"
+                                aria-expanded=move || transcript.get().to_string()
+                                disabled=!Capabilities::get().speech_recognition
+                                title=unsupported(
+                                    Capabilities::get().speech_recognition,
+                                    "speech recognition",
+                                )
+                                on:click=move |_| transcript.update(|show| *show = !*show)
+                            >
+                                "📝 Transcript"
+                            </button>
+                        </div>
+                        <Show when=move || transcript.get()>
+                            <Transcript show=transcript transcripts=transcripts/>
+                        </Show>
+                        <Show when=move || talk.get()>
+                            <Talk
+                                show=talk
+                                voice=speech_voice
+                                pitch=speech_pitch
+                                rate=speech_rate
+                            />
+                        </Show>
+                        <Show when=move || trainer.get()>
+                            <KeyTrainer show=trainer sounds=pack_sounds quack=quack/>
+                        </Show>
+                        <Sequencer
+                            sounds=pack_sounds
+                            pack=sound_pack
+                            in_meeting=in_meeting
+                            latency=latency
+                            playback_rate=playback_rate
+                            volume=volume
+                            intensity_curve=intensity_curve
+                            graph=graph
+                        />
+                    }
+                })}
+        </div>
+    }
+}
+
+/// Image of a duck, that falls back to a built-in placeholder if the image fails to load.
+#[component]
+pub fn duck_image(
+    #[prop(into)] duck: MaybeSignal<Duck>,
+    #[prop(into)] alt: MaybeSignal<String>,
+    #[prop(optional)] class: &'static str,
+    /// Plays a short squish animation when set.
+    #[prop(optional, into)]
+    squished: MaybeSignal<bool>,
+    /// How far the duck bobs up and opens its beak, from 0 to 1.
+    #[prop(optional, into)]
+    bob: MaybeSignal<f64>,
+) -> impl IntoView {
+    const PLACEHOLDER: &str = "data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' \
+        width='400' height='266' viewBox='0 0 400 266'%3E%3Ctext x='50%25' y='50%25' \
+        font-size='160' text-anchor='middle' dominant-baseline='central'%3E🦆%3C/text%3E%3C/svg%3E";
+
+    let failed = create_rw_signal(false);
+    create_effect(move |_| {
+        duck.track();
+        failed.set(false);
+    });
+
+    let error = move |_| {
+        report_error(
+            "content",
+            format!("failed loading image of {}", duck.get_untracked().name()),
+        );
+        failed.set(true);
+    };
+    let bobbing = move || bob.get() > 0.0;
+
+    view! {
+        <img
+            class=class
+            srcset=move || (!failed.get()).then(|| duck.get().preferred_srcset())
+            src=move || failed.get().then_some(PLACEHOLDER)
+            alt=alt
+            class:squished=squished
+            class:bobbing=bobbing
+            style=move || format!("--bob: {:.3}", bob.get())
+            on:error=error
+        />
+    }
+}
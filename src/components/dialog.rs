@@ -0,0 +1,13 @@
+use leptos::{component, prelude::*, view, Children, IntoView};
+
+#[component]
+pub fn dialog(children: Children, #[prop(into)] show: Signal<bool>) -> impl IntoView {
+    view! {
+        <div class="dialog" hidden=move || !show.get()>
+            <div class="dialog-backdrop"></div>
+            <div class="dialog-content">
+                <div class="settings-dialog">{children()}</div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use leptos::{
+    component, ev, on_cleanup, prelude::*, spawn_local, store_value, view, window_event_listener,
+    IntoView,
+};
+use web_sys::DragEvent;
+
+use crate::{audio::Sound, time::sleep, uploads};
+
+/// Overlay that shows up while files are dragged over the page, and imports dropped audio files as
+/// custom sounds.
+#[component]
+pub fn drop_zone(custom_sounds: RwSignal<Vec<Sound>>) -> impl IntoView {
+    /// How long the result of a drop stays visible.
+    const NOTICE_DURATION: Duration = Duration::from_secs(5);
+
+    // Drag events also fire for every child element that is entered and left, so only the
+    // outermost pair counts.
+    let depth = create_rw_signal(0_u32);
+    let notice = create_rw_signal(None::<String>);
+    // Bumped by every drop, so an older notice doesn't hide a newer one.
+    let notice_run = store_value(0_u32);
+
+    let carries_files = |event: &DragEvent| {
+        event
+            .data_transfer()
+            .is_some_and(|data| data.types().includes(&"Files".into(), 0))
+    };
+
+    let handles = [
+        window_event_listener(ev::dragenter, move |event| {
+            if carries_files(&event) {
+                depth.update(|depth| *depth += 1);
+            }
+        }),
+        window_event_listener(ev::dragleave, move |event| {
+            if carries_files(&event) {
+                depth.update(|depth| *depth = depth.saturating_sub(1));
+            }
+        }),
+        // Required to allow dropping at all.
+        window_event_listener(ev::dragover, move |event| {
+            if carries_files(&event) {
+                event.prevent_default();
+            }
+        }),
+        window_event_listener(ev::drop, move |event| {
+            if !carries_files(&event) {
+                return;
+            }
+            let Some(files) = event.data_transfer().and_then(|data| data.files()) else {
+                return;
+            };
+            // Otherwise the browser navigates away to the dropped file.
+            event.prevent_default();
+            depth.set(0);
+
+            let files = uploads::file_list(&files);
+            let count = files.len();
+            let rejected = uploads::import(files, custom_sounds);
+            notice.set(Some(if rejected.is_empty() {
+                format!("Adding {count} sound(s)")
+            } else {
+                format!(
+                    "Adding {} sound(s), skipped {}",
+                    count - rejected.len(),
+                    rejected.join(", ")
+                )
+            }));
+
+            notice_run.update_value(|run| *run += 1);
+            let run = notice_run.get_value();
+            spawn_local(async move {
+                sleep(NOTICE_DURATION).await;
+                if notice_run.get_value() == run {
+                    notice.set(None);
+                }
+            });
+        }),
+    ];
+    on_cleanup(move || {
+        for handle in handles {
+            handle.remove();
+        }
+    });
+
+    view! {
+        <div class="drop-zone" class:hidden=move || depth.get() == 0>
+            <span class="text-6xl">"🦆📂"</span>
+            <span class="text-2xl">"Drop audio files to add them as sounds"</span>
+        </div>
+        <p class="drop-notice" class:hidden=move || notice.with(Option::is_none) role="status">
+            {move || notice.get()}
+        </p>
+    }
+}
@@ -0,0 +1,54 @@
+use leptos::{component, prelude::*, view, CollectView, IntoView};
+
+use super::dialog::Dialog;
+use crate::branding::{asset_credits, footer_links};
+
+#[component]
+pub fn footer() -> impl IntoView {
+    let show_licenses = create_rw_signal(false);
+
+    view! {
+        <>
+            <div class="footer my-4 flex-initial">
+                <button class="link" on:click=move |_| show_licenses.set(true)>"Licenses"</button>
+                {footer_links()
+                    .map(|(label, url)| {
+                        view! {
+                            " • "
+                            <a class="link" href=url target="_blank">
+                                {label}
+                            </a>
+                        }
+                    })
+                    .collect_view()}
+            </div>
+            <Licenses show=show_licenses/>
+        </>
+    }
+}
+
+#[component]
+pub fn licenses(show: RwSignal<bool>) -> impl IntoView {
+    view! {
+        <Dialog show=show>
+            <p class="settings-header">"Asset Licenses"</p>
+            <div class="text-start">
+                {asset_credits()
+                    .map(|(text, name, url)| {
+                        view! {
+                            <p class="pb-3">
+                                {text} " "
+                                <a class="link" href=url target="_blank">
+                                    {name}
+                                </a>
+                            </p>
+                        }
+                    })
+                    .collect_view()}
+            </div>
+            <button class="btn p-2" on:click=move |_| show.set(false)>
+                "Close"
+            </button>
+        </Dialog>
+    }
+}
@@ -0,0 +1,250 @@
+use std::{fmt::Display, str::FromStr};
+
+use leptos::{
+    component, create_effect, create_node_ref, event_target_checked, event_target_value, html,
+    prelude::*, view, IntoView,
+};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{CanvasRenderingContext2d, Event, MouseEvent, PointerEvent};
+
+use crate::audio::CurvePoint;
+
+#[component]
+pub fn slider(
+    label: &'static str,
+    value: RwSignal<f64>,
+    default: f64,
+    min: f64,
+    max: f64,
+    /// Show the value as overridden, without losing it.
+    #[prop(optional, into)]
+    muted: MaybeSignal<bool>,
+) -> impl IntoView {
+    let input = move |event: Event| value.set(event_target_value(&event).parse().unwrap_throw());
+    let reset = move |_| value.set(default);
+
+    view! {
+        <div class="slider" class:muted=muted>
+            <span class="w-32">
+                {label}
+                <span class="text-sm text-slate-400" class:hidden=move || !muted.get()>
+                    " (muted)"
+                </span>
+            </span>
+            <input
+                class="grow"
+                type="range"
+                aria-label=label
+                min=min
+                max=max
+                step="any"
+                value=value
+                prop:value=value
+                on:change=input
+            />
+            <button class="btn py-0.5 px-1" on:click=reset>
+                "Reset"
+            </button>
+        </div>
+    }
+}
+
+/// Field for a whole number, that keeps the value within `min` and `max` if given.
+#[component]
+pub fn number_input<T>(
+    label: &'static str,
+    value: RwSignal<T>,
+    default: T,
+    #[prop(optional)] min: Option<T>,
+    #[prop(optional)] max: Option<T>,
+) -> impl IntoView
+where
+    T: Copy + Display + FromStr + Ord + 'static,
+{
+    let clamp = move |number: T| {
+        let number = min.map_or(number, |min| number.max(min));
+        max.map_or(number, |max| number.min(max))
+    };
+    let input = move |event: Event| match event_target_value(&event).parse() {
+        Ok(number) => value.set(clamp(number)),
+        // Shows the previous value again, instead of the invalid input.
+        Err(_) => value.update(|_| {}),
+    };
+    let reset = move |_| value.set(default);
+
+    view! {
+        <div class="slider">
+            <span class="w-32">{label}</span>
+            <input
+                class="grow settings-number"
+                type="number"
+                aria-label=label
+                min=min.map(|min| min.to_string())
+                max=max.map(|max| max.to_string())
+                prop:value=move || value.get().to_string()
+                on:change=input
+            />
+            <button class="btn py-0.5 px-1" on:click=reset>
+                "Reset"
+            </button>
+        </div>
+    }
+}
+
+#[component]
+pub fn toggle(
+    label: &'static str,
+    value: RwSignal<bool>,
+    /// Why the toggle can't be used, if it can't. Shown as tooltip.
+    #[prop(optional_no_strip)]
+    unsupported: Option<String>,
+) -> impl IntoView {
+    view! {
+        <label class="slider" class:opacity-50=unsupported.is_some() title=unsupported.clone()>
+            <span class="w-32">{label}</span>
+            <input
+                type="checkbox"
+                disabled=unsupported.is_some()
+                prop:checked=value
+                on:change=move |event| value.set(event_target_checked(&event))
+            />
+        </label>
+    }
+}
+
+/// Small canvas editor for a curve of control points. Clicking adds a point, dragging moves it and
+/// double-clicking removes it again. The first and last point always stay at the start and end.
+#[component]
+pub fn curve_editor(
+    label: &'static str,
+    value: RwSignal<Vec<CurvePoint>>,
+    default: &'static [CurvePoint],
+) -> impl IntoView {
+    const WIDTH: f64 = 256.0;
+    const HEIGHT: f64 = 96.0;
+    const HANDLE: f64 = 4.0;
+    /// Maximum distance in pixels to a control point, to count as hitting it.
+    const HIT: f64 = 8.0;
+
+    let canvas = create_node_ref::<html::Canvas>();
+    let dragging = create_rw_signal(None::<usize>);
+
+    // The canvas grows with the settings dialog, so pointer positions are relative to the size it
+    // is shown at, rather than its drawing size.
+    let size = move || {
+        let canvas = canvas.get_untracked().unwrap_throw();
+        (
+            f64::from(canvas.client_width()),
+            f64::from(canvas.client_height()),
+        )
+    };
+    let position = move |event: &MouseEvent| {
+        let (width, height) = size();
+        let time = (f64::from(event.offset_x()) / width).clamp(0.0, 1.0);
+        let gain = (1.0 - f64::from(event.offset_y()) / height).clamp(0.0, 1.0);
+        (time, gain)
+    };
+    let hit = move |time: f64, gain: f64| {
+        let (width, height) = size();
+        value.with(|points| {
+            points.iter().position(|point| {
+                ((point.time - time) * width).hypot((point.gain - gain) * height) <= HIT
+            })
+        })
+    };
+
+    create_effect(move |_| {
+        let points = value.get();
+        let Some(canvas) = canvas.get() else { return };
+        let ctx = canvas
+            .get_context("2d")
+            .unwrap_throw()
+            .unwrap_throw()
+            .unchecked_into::<CanvasRenderingContext2d>();
+
+        ctx.clear_rect(0.0, 0.0, WIDTH, HEIGHT);
+        ctx.set_stroke_style(&"#0ea5e9".into());
+        ctx.set_fill_style(&"#e2e8f0".into());
+        ctx.set_line_width(2.0);
+
+        ctx.begin_path();
+        for point in &points {
+            ctx.line_to(point.time * WIDTH, (1.0 - point.gain) * HEIGHT);
+        }
+        ctx.stroke();
+
+        for point in &points {
+            ctx.fill_rect(
+                point.time * WIDTH - HANDLE,
+                (1.0 - point.gain) * HEIGHT - HANDLE,
+                HANDLE * 2.0,
+                HANDLE * 2.0,
+            );
+        }
+    });
+
+    let pointer_down = move |event: PointerEvent| {
+        let (time, gain) = position(&event);
+        let index = hit(time, gain).unwrap_or_else(|| {
+            let mut index = 0;
+            value.update(|points| {
+                index = points.partition_point(|point| point.time < time).max(1);
+                points.insert(index, CurvePoint { time, gain });
+            });
+            index
+        });
+
+        canvas
+            .get_untracked()
+            .unwrap_throw()
+            .set_pointer_capture(event.pointer_id())
+            .unwrap_throw();
+        dragging.set(Some(index));
+    };
+    let pointer_move = move |event: PointerEvent| {
+        let Some(index) = dragging.get_untracked() else {
+            return;
+        };
+        let (time, gain) = position(&event);
+
+        value.update(|points| {
+            let last = points.len() - 1;
+            let time = match index {
+                0 => 0.0,
+                i if i == last => 1.0,
+                i => time.clamp(points[i - 1].time, points[i + 1].time),
+            };
+            points[index] = CurvePoint { time, gain };
+        });
+    };
+    let double_click = move |event: MouseEvent| {
+        let (time, gain) = position(&event);
+        if let Some(index) = hit(time, gain) {
+            value.update(|points| {
+                if index != 0 && index != points.len() - 1 {
+                    points.remove(index);
+                }
+            });
+        }
+    };
+    let reset = move |_| value.set(default.to_vec());
+
+    view! {
+        <div class="slider">
+            <span class="w-32">{label}</span>
+            <canvas
+                class="curve-editor"
+                width=WIDTH
+                height=HEIGHT
+                node_ref=canvas
+                on:pointerdown=pointer_down
+                on:pointermove=pointer_move
+                on:pointerup=move |_| dragging.set(None)
+                on:dblclick=double_click
+            ></canvas>
+            <button class="btn py-0.5 px-1 self-start" on:click=reset>
+                "Reset"
+            </button>
+        </div>
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use leptos::{component, prelude::*, spawn_local, store_value, view, IntoView};
+
+use crate::{audio::Sound, microphone::create_pause_detector, time::sleep};
+
+/// Nudges to keep talking, like a listener that nods along.
+const PROMPTS: &[&str] = &[
+    "…and then what happens?",
+    "…and why would it do that?",
+    "…what did you expect instead?",
+    "…go on, I'm listening.",
+];
+
+/// Quacks and shows a prompt whenever the user pauses for longer than `pause` seconds while
+/// talking into the microphone, to keep them explaining.
+#[component]
+pub fn interjection(
+    #[prop(into)] level: Signal<f64>,
+    #[prop(into)] enabled: Signal<bool>,
+    #[prop(into)] pause: Signal<f64>,
+    /// Hold back while set, as interjecting is an automatic sound.
+    #[prop(into)]
+    in_meeting: Signal<bool>,
+    #[prop(into)] quack: SignalSetter<Option<Sound>>,
+) -> impl IntoView {
+    /// How long a prompt stays visible.
+    const PROMPT_DURATION: Duration = Duration::from_secs(4);
+
+    let prompt = create_rw_signal(None::<&'static str>);
+    // Bumped by every interjection, so an older prompt doesn't hide a newer one.
+    let prompt_run = store_value(0_u32);
+
+    create_pause_detector(level, pause, move || {
+        if !enabled.get_untracked() || in_meeting.get_untracked() {
+            return;
+        }
+        quack.set(None);
+        prompt.set(Some(PROMPTS[fastrand::usize(..PROMPTS.len())]));
+
+        prompt_run.update_value(|run| *run += 1);
+        let run = prompt_run.get_value();
+        spawn_local(async move {
+            sleep(PROMPT_DURATION).await;
+            if prompt_run.get_value() == run {
+                prompt.set(None);
+            }
+        });
+    });
+
+    view! {
+        <p class="interjection" class:hidden=move || prompt.with(Option::is_none) role="status">
+            "🦆 "
+            {move || prompt.get()}
+        </p>
+    }
+}
@@ -0,0 +1,26 @@
+mod bench;
+mod calibration;
+mod clip;
+mod content;
+mod dialog;
+mod drop_zone;
+mod footer;
+mod inputs;
+mod interjection;
+mod navbar;
+mod palette;
+mod recorder;
+mod sequencer;
+pub mod settings;
+mod sound_link;
+mod sounds;
+mod talk;
+mod trainer;
+mod transcript;
+mod visualizer;
+
+pub use self::{
+    bench::Bench, content::Content, drop_zone::DropZone, footer::Footer,
+    interjection::Interjection, navbar::Navbar, palette::CommandPalette, settings::Settings,
+    sound_link::SoundLink,
+};
@@ -0,0 +1,41 @@
+use leptos::{component, prelude::*, view, IntoView};
+
+use crate::branding::{APP_NAME, LOGO};
+
+#[component]
+pub fn navbar(
+    #[prop(into)] settings: SignalSetter<bool>,
+    muted: RwSignal<bool>,
+    classroom: bool,
+) -> impl IntoView {
+    let settings = move |_| settings.set(true);
+    let toggle_mute = move |_| muted.update(|muted| *muted = !*muted);
+
+    view! {
+        <div class="flex gap-2 place-items-center">
+            <div class="flex gap-2 items-center text-2xl">
+                {match LOGO {
+                    Some(logo) => view! { <img class="h-8" src=logo alt=""/> }.into_view(),
+                    None => "🦆".into_view(),
+                }}
+                {APP_NAME}
+            </div>
+            <button
+                class="btn p-2"
+                aria-label="Mute"
+                aria-pressed=move || muted.get().to_string()
+                on:click=toggle_mute
+            >
+                {move || if muted.get() { "🔇" } else { "🔊" }}
+            </button>
+            {(!classroom)
+                .then(|| {
+                    view! {
+                        <button class="btn p-2" on:click=settings>
+                            "Settings"
+                        </button>
+                    }
+                })}
+        </div>
+    }
+}
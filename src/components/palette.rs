@@ -0,0 +1,132 @@
+use leptos::{
+    component, create_node_ref, ev, event_target_value, html, on_cleanup, prelude::*,
+    request_animation_frame, view, window_event_listener, CollectView, IntoView,
+};
+use wasm_bindgen::UnwrapThrowExt;
+use web_sys::KeyboardEvent;
+
+use super::settings::fuzzy_match;
+use crate::commands::{commands, Command};
+
+/// Searchable list of all [registered](crate::commands::register_command) commands, that opens
+/// with Ctrl+K.
+#[component]
+pub fn command_palette() -> impl IntoView {
+    let show = create_rw_signal(false);
+    // Taken when the palette opens, so the list doesn't shift while picking from it.
+    let available = create_rw_signal(Vec::<Command>::new());
+    let query = create_rw_signal(String::new());
+    let selected = create_rw_signal(0_usize);
+    let input = create_node_ref::<html::Input>();
+
+    let matches = move || {
+        available.with(|commands| {
+            query.with(|query| {
+                commands
+                    .iter()
+                    .filter(|command| fuzzy_match(query, &command.label))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+        })
+    };
+
+    let open = move || {
+        available.set(commands());
+        query.set(String::new());
+        selected.set(0);
+        show.set(true);
+        // Only focusable once the palette is visible.
+        request_animation_frame(move || {
+            if let Some(input) = input.get_untracked() {
+                input.focus().unwrap_throw();
+            }
+        });
+    };
+    let run = move |command: &Command| {
+        show.set(false);
+        (command.run)();
+    };
+
+    let shortcut = window_event_listener(ev::keydown, move |event: KeyboardEvent| {
+        if (event.ctrl_key() || event.meta_key()) && event.key().eq_ignore_ascii_case("k") {
+            event.prevent_default();
+            if show.get_untracked() {
+                show.set(false);
+            } else {
+                open();
+            }
+        }
+    });
+    on_cleanup(move || shortcut.remove());
+
+    let keydown = move |event: KeyboardEvent| {
+        let count = matches().len();
+        match event.key().as_str() {
+            "ArrowDown" if count > 0 => selected.update(|index| *index = (*index + 1) % count),
+            "ArrowUp" if count > 0 => {
+                selected.update(|index| *index = (*index + count - 1) % count);
+            }
+            "Enter" => {
+                if let Some(command) = matches().get(selected.get_untracked()) {
+                    run(command);
+                }
+            }
+            "Escape" => show.set(false),
+            _ => return,
+        }
+        event.prevent_default();
+    };
+
+    let command_view = move |(index, command): (usize, Command)| {
+        let label = command.label.clone();
+        view! {
+            <li
+                class="command"
+                role="option"
+                aria-selected=move || (selected.get() == index).to_string()
+                on:pointerenter=move |_| selected.set(index)
+                on:click=move |_| run(&command)
+            >
+                {label}
+            </li>
+        }
+    };
+
+    view! {
+        <div
+            class="command-palette"
+            class:hidden=move || !show.get()
+            on:click=move |_| show.set(false)
+        >
+            <div
+                class="command-palette-content"
+                role="dialog"
+                aria-label="Command palette"
+                on:click=|event| event.stop_propagation()
+            >
+                <input
+                    class="settings-search"
+                    type="search"
+                    placeholder="Type a command…"
+                    role="combobox"
+                    aria-controls="commands"
+                    aria-expanded="true"
+                    node_ref=input
+                    prop:value=query
+                    on:input=move |event| {
+                        query.set(event_target_value(&event));
+                        selected.set(0);
+                    }
+                    on:keydown=keydown
+                />
+                <ul id="commands" role="listbox">
+                    {move || matches().into_iter().enumerate().map(command_view).collect_view()}
+                </ul>
+                <p class="settings-hint" class:hidden=move || !matches().is_empty()>
+                    "No matching command"
+                </p>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,166 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use js_sys::{Array, Promise};
+use leptos::{component, prelude::*, spawn_local, view, IntoView, View};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue, UnwrapThrowExt};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Blob, BlobEvent, BlobPropertyBag, File, FilePropertyBag, MediaRecorder, MediaStream, Url,
+};
+
+use crate::{
+    audio::{is_not_allowed, Sound},
+    capabilities::{unsupported, Capabilities},
+    diagnostics::report_error,
+    microphone::{close_microphone, open_microphone},
+    time::sleep,
+    uploads,
+};
+
+#[derive(Clone)]
+enum State {
+    Idle,
+    /// The microphone permission was refused.
+    Denied,
+    /// Seconds left until the recording starts.
+    Countdown(u8),
+    Recording,
+    /// Finished recording, with an object URL of it for the preview.
+    Recorded(Blob, String),
+}
+
+/// Records a short sound with the microphone, that can be kept as custom sound after listening to
+/// it.
+#[component]
+pub fn recorder(custom_sounds: RwSignal<Vec<Sound>>) -> impl IntoView {
+    let capabilities = Capabilities::get();
+    let supported = capabilities.recording && capabilities.microphone && capabilities.indexed_db;
+    let state = create_rw_signal(State::Idle);
+
+    let start = move |_| {
+        spawn_local(async move {
+            match record(state).await {
+                Ok(blob) => {
+                    let url = Url::create_object_url_with_blob(&blob).unwrap_throw();
+                    state.set(State::Recorded(blob, url));
+                }
+                Err(e) if is_not_allowed(&e) => state.set(State::Denied),
+                Err(e) => {
+                    report_error("recorder", format!("failed recording:\n{e:?}"));
+                    state.set(State::Idle);
+                }
+            }
+        });
+    };
+
+    let finish = move |keep: bool| {
+        let State::Recorded(blob, url) = state.get_untracked() else {
+            return;
+        };
+        Url::revoke_object_url(&url).ok();
+        state.set(State::Idle);
+
+        if keep {
+            let file = File::new_with_blob_sequence_and_options(
+                &Array::of1(&blob),
+                "Recording",
+                FilePropertyBag::new().type_(&blob.type_()),
+            )
+            .unwrap_throw();
+            uploads::import(vec![file], custom_sounds);
+        }
+    };
+
+    let record_button = move || -> View {
+        view! {
+            <button
+                class="btn py-0.5 px-1"
+                disabled=!supported
+                title=unsupported(supported, "microphone recording")
+                on:click=start
+            >
+                "🎙 Record"
+            </button>
+        }
+        .into_view()
+    };
+
+    view! {
+        <div class="slider">
+            <span class="w-32">"Record a sound"</span>
+            {move || match state.get() {
+                State::Idle => record_button(),
+                State::Denied => {
+                    view! {
+                        {record_button()}
+                        <span class="text-sm text-amber-200">"Microphone access was denied"</span>
+                    }
+                        .into_view()
+                }
+                State::Countdown(seconds) => {
+                    view! { <span aria-live="polite">{format!("Recording in {seconds}…")}</span> }
+                        .into_view()
+                }
+                State::Recording => {
+                    view! { <span aria-live="polite">"🔴 Recording…"</span> }.into_view()
+                }
+                State::Recorded(_, url) => {
+                    view! {
+                        <audio class="grow" controls src=url></audio>
+                        <button class="btn py-0.5 px-1" on:click=move |_| finish(true)>
+                            "Keep"
+                        </button>
+                        <button class="btn py-0.5 px-1" on:click=move |_| finish(false)>
+                            "Discard"
+                        </button>
+                    }
+                        .into_view()
+                }
+            }}
+        </div>
+    }
+}
+
+/// Ask for the microphone, count down and record a short sound.
+async fn record(state: RwSignal<State>) -> Result<Blob, JsValue> {
+    const COUNTDOWN: u8 = 3;
+
+    let stream = open_microphone().await?;
+
+    // Only after the permission prompt, so the countdown doesn't run out while it is open.
+    for seconds in (1..=COUNTDOWN).rev() {
+        state.set(State::Countdown(seconds));
+        sleep(Duration::from_secs(1)).await;
+    }
+    state.set(State::Recording);
+
+    let recorded = capture(&stream).await;
+    close_microphone(&stream);
+
+    recorded
+}
+
+async fn capture(stream: &MediaStream) -> Result<Blob, JsValue> {
+    /// Long enough for a quack, short enough to not catch much else.
+    const DURATION: Duration = Duration::from_secs(2);
+
+    let recorder = MediaRecorder::new_with_media_stream(stream)?;
+    let chunks = Rc::new(RefCell::new(Vec::<Blob>::new()));
+    let data_available = Closure::<dyn FnMut(BlobEvent)>::new({
+        let chunks = Rc::clone(&chunks);
+        move |event: BlobEvent| chunks.borrow_mut().extend(event.data())
+    });
+    recorder.set_ondataavailable(Some(data_available.as_ref().unchecked_ref()));
+    let stopped = Promise::new(&mut |resolve, _| recorder.set_onstop(Some(&resolve)));
+
+    recorder.start()?;
+    sleep(DURATION).await;
+    recorder.stop()?;
+    JsFuture::from(stopped).await?;
+
+    let parts = chunks.borrow().iter().collect::<Array>();
+    Blob::new_with_blob_sequence_and_options(
+        &parts,
+        BlobPropertyBag::new().type_(&recorder.mime_type()),
+    )
+}
@@ -0,0 +1,206 @@
+use std::{collections::HashMap, time::Duration};
+
+use leptos::{
+    component, create_effect, create_memo, prelude::*, set_timeout, spawn_local, store_value, view,
+    CollectView, IntoView,
+};
+
+use super::inputs::Slider;
+use crate::{
+    audio::{get_or_create_graph, resume, AudioGraph, Clip, CurvePoint, SharedGraph, Sound},
+    commands::register_command,
+    diagnostics::report_error,
+    time::sleep,
+};
+
+const STEPS: usize = 8;
+const DEFAULT_TEMPO: f64 = 120.0;
+/// How far ahead of the audio clock steps are scheduled, in seconds. Long enough to cover a late
+/// timer, short enough that tempo changes apply right away.
+const LOOKAHEAD: f64 = 0.1;
+/// How often the scheduler wakes up, to schedule the steps that entered the look-ahead window.
+const INTERVAL: Duration = Duration::from_millis(25);
+
+/// Step sequencer, that plays a grid of sounds in a loop. Every row is a sound and every column an
+/// eighth note at the chosen tempo.
+#[component]
+pub fn sequencer(
+    #[prop(into)] sounds: Signal<Vec<Sound>>,
+    /// ID of the selected sound pack. The grid is cleared when it changes.
+    #[prop(into)]
+    pack: Signal<String>,
+    /// Keeps the steps going without sound while set.
+    #[prop(into)]
+    in_meeting: Signal<bool>,
+    /// Delay until a sound is heard on this device, in milliseconds. Sounds are scheduled early by
+    /// as much, so they are heard right on their step.
+    #[prop(into)]
+    latency: Signal<f64>,
+    #[prop(into)] playback_rate: Signal<f64>,
+    #[prop(into)] volume: Signal<f64>,
+    #[prop(into)] intensity_curve: Signal<Vec<CurvePoint>>,
+    graph: SharedGraph,
+) -> impl IntoView {
+    let grid = create_rw_signal(Vec::<[bool; STEPS]>::new());
+    let clear = move || grid.set(vec![[false; STEPS]; sounds.with_untracked(Vec::len)]);
+    // A different pack has different rows, so the old steps don't fit anymore.
+    let pack = create_memo(move |_| pack.get());
+    create_effect(move |_| {
+        pack.track();
+        clear();
+    });
+    // The rows of the same pack can still change, like when the pack manifest finishes loading,
+    // which keeps the steps that are already placed.
+    create_effect(move |_| {
+        let rows = sounds.with(Vec::len);
+        grid.update(|grid| grid.resize(rows, [false; STEPS]));
+    });
+    let tempo = create_rw_signal(DEFAULT_TEMPO);
+    let playing = create_rw_signal(false);
+    let current = create_rw_signal(None::<usize>);
+    // Bumped on every start and stop, so a running loop notices that it should end.
+    let run = store_value(0_u32);
+
+    let toggle_playing = move || {
+        run.update_value(|run| *run += 1);
+        if playing.get_untracked() {
+            playing.set(false);
+            current.set(None);
+            return;
+        }
+
+        playing.set(true);
+        let this_run = run.get_value();
+        spawn_local(async move {
+            let graph = get_or_create_graph(graph);
+            if !resume(&graph).await {
+                playing.set(false);
+                return;
+            }
+
+            // Decoded up front, so the first steps aren't late. Sounds that show up later are
+            // decoded on their first step, and failures are only reported once.
+            let mut clips = HashMap::<&'static str, Option<Clip>>::new();
+            for sound in sounds.get_untracked() {
+                clips.insert(sound.name, decode(&graph, sound).await);
+            }
+
+            // Steps are timed on the audio clock and scheduled slightly ahead, so neither late
+            // timers nor a busy page make them drift or stumble. `next` is when the step should be
+            // heard, and its sounds start earlier by the latency.
+            let latency = move || latency.get_untracked() / 1000.0;
+            let mut next = graph.context.current_time() + LOOKAHEAD + latency();
+            let mut tick = 0_u64;
+            while run.get_value() == this_run {
+                // Timers of hidden pages may wake up seconds late, and the missed steps are better
+                // skipped than played all at once.
+                next = next.max(graph.context.current_time() + latency());
+                while next - latency() < graph.context.current_time() + LOOKAHEAD {
+                    let step = (tick % STEPS as u64) as usize;
+                    let until = next - graph.context.current_time();
+                    set_timeout(
+                        move || {
+                            if run.get_value() == this_run {
+                                current.set(Some(step));
+                            }
+                        },
+                        Duration::from_secs_f64(until.max(0.0)),
+                    );
+
+                    if !in_meeting.get_untracked() {
+                        let active = grid.with_untracked(|grid| {
+                            grid.iter()
+                                .zip(sounds.get_untracked())
+                                .filter_map(|(row, sound)| row[step].then_some(sound))
+                                .collect::<Vec<_>>()
+                        });
+                        for sound in active {
+                            if !clips.contains_key(sound.name) {
+                                clips.insert(sound.name, decode(&graph, sound).await);
+                            }
+                            if let Some(clip) = &clips[sound.name] {
+                                graph.schedule(
+                                    clip,
+                                    next - latency(),
+                                    playback_rate.get_untracked(),
+                                    volume.get_untracked(),
+                                    &intensity_curve.get_untracked(),
+                                );
+                            }
+                        }
+                    }
+
+                    next += 60.0 / tempo.get_untracked() / 2.0;
+                    tick += 1;
+                }
+
+                sleep(INTERVAL).await;
+            }
+        });
+    };
+
+    register_command("Start or stop the sequencer", toggle_playing);
+
+    let row_view = move |(row, sound): (usize, Sound)| {
+        let cells = (0..STEPS)
+            .map(|step| {
+                // The grid may still be sized for the previous pack, while the rows are replaced.
+                let active = move || grid.with(|grid| grid.get(row).is_some_and(|row| row[step]));
+                let toggle = move |_| {
+                    grid.update(|grid| {
+                        if let Some(row) = grid.get_mut(row) {
+                            row[step] = !row[step];
+                        }
+                    });
+                };
+                view! {
+                    <button
+                        class="sequencer-step"
+                        class:current=move || current.get() == Some(step)
+                        aria-pressed=move || active().to_string()
+                        aria-label=format!("{} on step {}", sound.name, step + 1)
+                        on:click=toggle
+                    ></button>
+                }
+            })
+            .collect_view();
+
+        view! {
+            <div class="flex gap-1 items-center">
+                <span class="w-16 text-sm text-left">{sound.name}</span>
+                {cells}
+            </div>
+        }
+    };
+
+    view! {
+        <details class="sequencer">
+            <summary class="cursor-pointer">"🎹 Sequencer"</summary>
+            <div class="flex flex-col gap-1 mt-2">
+                {move || sounds.get().into_iter().enumerate().map(row_view).collect_view()}
+                <Slider label="Tempo (BPM)" value=tempo default=DEFAULT_TEMPO min=60.0 max=240.0/>
+                <div class="flex gap-2 justify-center">
+                    <button class="btn py-1 px-2" on:click=move |_| toggle_playing()>
+                        {move || if playing.get() { "⏹ Stop" } else { "▶ Play" }}
+                    </button>
+                    <button class="btn py-1 px-2" on:click=move |_| clear()>
+                        "Clear"
+                    </button>
+                </div>
+            </div>
+        </details>
+    }
+}
+
+async fn decode(graph: &AudioGraph, sound: Sound) -> Option<Clip> {
+    sound
+        .decode(&graph.context)
+        .await
+        .map_err(|e| {
+            report_error(
+                "sequencer",
+                format!("failed decoding sound `{}`:\n{e:?}", sound.name),
+            );
+        })
+        .ok()
+}
@@ -0,0 +1,1065 @@
+use std::iter;
+
+use leptos::{
+    component, create_effect, ev, event_target, event_target_checked, event_target_value,
+    prelude::*, spawn_local, view, window, window_event_listener, Children, CollectView, For,
+    IntoView, Show,
+};
+use wasm_bindgen::{JsValue, UnwrapThrowExt};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+
+use super::{
+    calibration::LatencyCalibration,
+    content::DuckImage,
+    dialog::Dialog,
+    inputs::{CurveEditor, NumberInput, Slider, Toggle},
+    recorder::Recorder,
+    sound_link::copy_sound_link,
+};
+use crate::{
+    audio::{
+        all_sounds, weight, CurvePoint, DisabledSounds, FavoriteSounds, Sound, SoundWeights,
+        MAX_WEIGHT,
+    },
+    capabilities::{unsupported, Capabilities},
+    commands::register_command,
+    diagnostics::{recent_errors, report_error, write_clipboard, Diagnostics},
+    ducks::Duck,
+    keys::{capture_next_key, default_keybindings, key_label, Action, Keybindings},
+    packs::SoundPack,
+    speech::create_voices,
+    uploads,
+};
+
+pub const DEFAULT_PLAYBACK_RATE: f64 = 0.8;
+pub const DEFAULT_VOLUME: f64 = 0.1;
+pub const DEFAULT_MUTED: bool = false;
+pub const DEFAULT_LIMITER: bool = true;
+pub const DEFAULT_REVERB: bool = false;
+pub const DEFAULT_ECHO: bool = false;
+pub const DEFAULT_LOW_POWER: bool = false;
+pub const DEFAULT_READ_ALOUD: bool = false;
+pub const DEFAULT_MEETING_HOURS: bool = false;
+pub const DEFAULT_MEETING_START: u8 = 9;
+pub const DEFAULT_MEETING_END: u8 = 17;
+/// Empty for the browser's default voice.
+pub const DEFAULT_SPEECH_VOICE: &str = "";
+/// Well above a normal voice, to sound like a duck.
+pub const DEFAULT_SPEECH_PITCH: f64 = 2.0;
+pub const DEFAULT_SPEECH_RATE: f64 = 1.2;
+pub const DEFAULT_LATENCY: f64 = 0.0;
+pub const DEFAULT_MICROPHONE: bool = false;
+pub const DEFAULT_INTERJECT: bool = true;
+/// Seconds of silence, after which the duck interjects.
+pub const DEFAULT_INTERJECT_PAUSE: f64 = 5.0;
+pub const MAX_INTERJECT_PAUSE: f64 = 30.0;
+pub const DEFAULT_LOOP_GAP: f64 = 2.0;
+pub const MAX_LOOP_GAP: f64 = 10.0;
+pub const DEFAULT_BRIDGE: bool = false;
+pub const DEFAULT_BRIDGE_PORT: u16 = 7777;
+pub const DEFAULT_BOOST: f64 = 1.0;
+pub const MAX_BOOST: f64 = 4.0;
+pub const DEFAULT_MAX_VOICES: u8 = 4;
+pub const MAX_VOICES: u8 = 16;
+pub const DEFAULT_PITCH_VARIATION: f64 = 0.0;
+/// Largest random offset of the playback rate, as fraction of the configured one.
+pub const MAX_PITCH_VARIATION: f64 = 0.25;
+pub const DEFAULT_INTENSITY_CURVE: &[CurvePoint] = &[
+    CurvePoint {
+        time: 0.0,
+        gain: 1.0,
+    },
+    CurvePoint {
+        time: 1.0,
+        gain: 1.0,
+    },
+];
+
+#[component]
+pub fn settings(
+    show: RwSignal<bool>,
+    #[prop(into)] playback_rate: RwSignal<f64>,
+    #[prop(into)] volume: RwSignal<f64>,
+    muted: RwSignal<bool>,
+    intensity_curve: RwSignal<Vec<CurvePoint>>,
+    limiter: RwSignal<bool>,
+    boost: RwSignal<f64>,
+    max_voices: RwSignal<u8>,
+    pitch_variation: RwSignal<f64>,
+    reverb: RwSignal<bool>,
+    echo: RwSignal<bool>,
+    low_power: RwSignal<bool>,
+    read_aloud: RwSignal<bool>,
+    meeting: RwSignal<bool>,
+    meeting_hours: RwSignal<bool>,
+    meeting_start: RwSignal<u8>,
+    meeting_end: RwSignal<u8>,
+    #[prop(into)] in_meeting: Signal<bool>,
+    speech_voice: RwSignal<String>,
+    speech_pitch: RwSignal<f64>,
+    speech_rate: RwSignal<f64>,
+    latency: RwSignal<f64>,
+    microphone: RwSignal<bool>,
+    interject: RwSignal<bool>,
+    interject_pause: RwSignal<f64>,
+    loop_gap: RwSignal<f64>,
+    keybindings: RwSignal<Keybindings>,
+    sound_weights: RwSignal<SoundWeights>,
+    disabled_sounds: RwSignal<DisabledSounds>,
+    favorite_sounds: RwSignal<FavoriteSounds>,
+    #[prop(into)] sound_packs: Signal<Vec<SoundPack>>,
+    sound_pack: RwSignal<String>,
+    #[prop(into)] pack_sounds: Signal<Vec<Sound>>,
+    custom_sounds: RwSignal<Vec<Sound>>,
+    bridge: RwSignal<bool>,
+    bridge_port: RwSignal<u16>,
+    selection: RwSignal<Duck>,
+    #[prop(into)] quack: SignalSetter<Option<Sound>>,
+) -> impl IntoView {
+    let capabilities = Capabilities::get();
+    let search = create_rw_signal(String::new());
+    let tab = create_rw_signal(SettingsTab::Audio);
+    let close = move |_| {
+        show.set(false);
+        search.set(String::new());
+    };
+
+    let duck_view = move |duck: Duck| {
+        let select = move |_| {
+            if selection.get() != duck {
+                selection.set(duck);
+                quack.set(Some(duck.preview()));
+            }
+        };
+        let description = format!("duck-{}-description", duck.id());
+
+        view! {
+            <label>
+                <input
+                    class="sr-only peer"
+                    type="radio"
+                    name="duck"
+                    aria-label=duck.name()
+                    aria-describedby=description.clone()
+                    checked=move || selection.get() == duck
+                    on:click=select
+                />
+                <DuckImage duck=duck alt=String::new() class="settings-duck-image"/>
+                <span id=description class="sr-only">
+                    {duck.description()}
+                </span>
+            </label>
+        }
+    };
+
+    let voice_view = move |duck: Duck| {
+        let voice = move || {
+            pack_sounds.with(|pack| {
+                duck.voiced(pack)
+                    .iter()
+                    .map(|sound| sound.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+        };
+        view! {
+            <p class="slider" class:font-bold=move || selection.get() == duck>
+                <span class="w-32">{duck.name()}</span>
+                <span class="grow text-sm text-slate-300">{voice}</span>
+            </p>
+        }
+    };
+
+    let sounds =
+        move || pack_sounds.with(|pack| custom_sounds.with(|custom| all_sounds(pack, custom)));
+    // Sounds that the selected duck actually picks from, as its voice narrows down the pack.
+    let playable = create_memo(move |_| {
+        pack_sounds.with(|pack| {
+            custom_sounds.with(|custom| {
+                disabled_sounds.with(|disabled| selection.get().playable(pack, custom, disabled))
+            })
+        })
+    });
+    let pack_option = move |pack: SoundPack| {
+        let selected = {
+            let id = pack.id.clone();
+            move || sound_pack.with(|selected| *selected == id)
+        };
+        view! {
+            <option value=pack.id prop:selected=selected>
+                {pack.name}
+            </option>
+        }
+    };
+
+    let voices = create_voices();
+    let voice_option = move |name: String| {
+        let selected = {
+            let name = name.clone();
+            move || speech_voice.with(|selected| *selected == name)
+        };
+        view! {
+            <option value=name.clone() prop:selected=selected>
+                {name}
+            </option>
+        }
+    };
+
+    let remove = move |sound: Sound| {
+        uploads::remove(sound, custom_sounds);
+        // A later sound of the same name shouldn't inherit them.
+        sound_weights.update(|weights| {
+            weights.remove(sound.name);
+        });
+        disabled_sounds.update(|disabled| {
+            disabled.remove(sound.name);
+        });
+        favorite_sounds.update(|favorites| {
+            favorites.remove(sound.name);
+        });
+    };
+
+    let sound_view = move |sound: Sound| {
+        let weight = move || weight(&sound_weights.get(), sound);
+        let enabled = move || disabled_sounds.with(|disabled| !disabled.contains(sound.name));
+        let toggle = move |event| {
+            let enabled = event_target_checked(&event);
+            disabled_sounds.update(|disabled| {
+                if enabled {
+                    disabled.remove(sound.name);
+                } else {
+                    disabled.insert(sound.name.to_owned());
+                }
+            });
+        };
+        let favorite = move || favorite_sounds.with(|favorites| favorites.contains(sound.name));
+        let toggle_favorite = move |_| {
+            favorite_sounds.update(|favorites| {
+                if !favorites.remove(sound.name) {
+                    favorites.insert(sound.name.to_owned());
+                }
+            });
+        };
+        let input = move |event| {
+            if let Ok(weight) = event_target_value(&event).parse() {
+                sound_weights.update(|weights| {
+                    weights.insert(sound.name.to_owned(), weight);
+                });
+            }
+        };
+
+        let voiced = move || playable.with(|playable| playable.contains(&sound));
+
+        view! {
+            <div class="slider">
+                <label
+                    class="flex gap-1 w-32"
+                    class:opacity-50=move || !voiced()
+                    title=move || {
+                        (!voiced())
+                            .then(|| format!("Not played by {}", selection.get().name()))
+                    }
+                >
+                    <input type="checkbox" prop:checked=enabled on:change=toggle/>
+                    {sound.name}
+                </label>
+                <input
+                    class="grow"
+                    type="range"
+                    aria-label=format!("Weight of {}", sound.name)
+                    disabled=move || !enabled()
+                    min=0
+                    max=MAX_WEIGHT
+                    step=1
+                    prop:value=weight
+                    on:change=input
+                />
+                <span class="w-12" aria-hidden="true">
+                    {move || match weight() {
+                        0 => "never".to_owned(),
+                        weight => format!("{weight}×"),
+                    }}
+                </span>
+                <button
+                    class="btn py-0.5 px-1"
+                    aria-label=format!("Favorite {}", sound.name)
+                    aria-pressed=move || favorite().to_string()
+                    on:click=toggle_favorite
+                >
+                    {move || if favorite() { "★" } else { "☆" }}
+                </button>
+                <button
+                    class="btn py-0.5 px-1"
+                    aria-label=format!("Preview {}", sound.name)
+                    on:click=move |_| quack.set(Some(sound))
+                >
+                    "▶"
+                </button>
+                {if sound.is_custom() {
+                    view! {
+                        <button
+                            class="btn py-0.5 px-1"
+                            aria-label=format!("Delete {}", sound.name)
+                            on:click=move |_| remove(sound)
+                        >
+                            "🗑"
+                        </button>
+                    }
+                } else {
+                    // Custom sounds only exist in this browser, so there is nothing to link to.
+                    view! {
+                        <button
+                            class="btn py-0.5 px-1"
+                            aria-label=format!("Copy link to {}", sound.name)
+                            disabled=!capabilities.clipboard
+                            on:click=move |_| copy_sound_link(sound)
+                        >
+                            "🔗"
+                        </button>
+                    }
+                }}
+            </div>
+        }
+    };
+
+    // Action that waits for its new key.
+    let capturing = create_rw_signal(None::<Action>);
+    let shortcut_view = move |action: Action| {
+        let capture = move |_| {
+            capturing.set(Some(action));
+            capture_next_key(move |key| {
+                capturing.set(None);
+                let Some(key) = key else { return };
+                keybindings.update(|bindings| {
+                    // Every key triggers at most one action.
+                    bindings.retain(|_, bound| *bound != key);
+                    bindings.insert(action, key);
+                });
+            });
+        };
+        let clear = move |_| {
+            keybindings.update(|bindings| {
+                bindings.remove(&action);
+            });
+        };
+        let key = move || {
+            if capturing.get() == Some(action) {
+                return "Press a key…".to_owned();
+            }
+            keybindings.with(|bindings| {
+                bindings
+                    .get(&action)
+                    .map_or_else(|| "None".to_owned(), |key| key_label(key))
+            })
+        };
+
+        view! {
+            <div class="slider">
+                <span class="w-32">{action.label()}</span>
+                <button
+                    class="grow btn py-0.5 px-1"
+                    aria-label=format!("Change shortcut of {}", action.label())
+                    on:click=capture
+                >
+                    <kbd>{key}</kbd>
+                </button>
+                <button
+                    class="btn py-0.5 px-1"
+                    aria-label=format!("Remove shortcut of {}", action.label())
+                    on:click=clear
+                >
+                    "✕"
+                </button>
+            </div>
+        }
+    };
+
+    // Files of the last upload, that weren't accepted.
+    let rejected = create_rw_signal(Vec::<String>::new());
+    let upload = move |event: ev::Event| {
+        let input = event_target::<HtmlInputElement>(&event);
+        let Some(files) = input.files() else {
+            return;
+        };
+        let files = uploads::file_list(&files);
+        // Allows picking the same file again, once it was deleted.
+        input.set_value("");
+        rejected.set(uploads::import(files, custom_sounds));
+    };
+
+    for target in SettingsTab::iter() {
+        register_command(format!("Settings: {}", target.label()), move || {
+            tab.set(target);
+            show.set(true);
+        });
+    }
+
+    let open_from_hash = move || {
+        if let Some(target) = SettingsTab::from_hash(&window().location().hash().unwrap_throw()) {
+            tab.set(target);
+            show.set(true);
+        }
+    };
+
+    open_from_hash();
+    window_event_listener(ev::hashchange, move |_| open_from_hash());
+
+    create_effect(move |_| {
+        let hash = if show.get() {
+            format!("#settings/{}", tab.get().anchor())
+        } else {
+            String::new()
+        };
+
+        let location = window().location();
+        let current = location.hash().unwrap_throw();
+        // Other hashes, like links to a sound, are left to whatever handles them.
+        if current != hash && (show.get() || current.starts_with("#settings")) {
+            let url = format!(
+                "{}{}{hash}",
+                location.pathname().unwrap_throw(),
+                location.search().unwrap_throw()
+            );
+            window()
+                .history()
+                .unwrap_throw()
+                .replace_state_with_url(&JsValue::NULL, "", Some(&url))
+                .unwrap_throw();
+        }
+    });
+
+    let tab_view = move |target: SettingsTab| {
+        view! {
+            <button
+                class="settings-tab"
+                role="tab"
+                aria-selected=move || (tab.get() == target).to_string()
+                on:click=move |_| tab.set(target)
+            >
+                {target.label()}
+            </button>
+        }
+    };
+
+    view! {
+        <Dialog show=show>
+            <p class="settings-header">"Settings"</p>
+            <input
+                class="settings-search"
+                type="search"
+                placeholder="Search settings…"
+                prop:value=search
+                on:input=move |event| search.set(event_target_value(&event))
+            />
+            <div class="settings-tabs" role="tablist" class:hidden=move || !search.get().is_empty()>
+                <For each=SettingsTab::iter key=|tab| *tab children=tab_view/>
+            </div>
+            <Searchable label="Playback rate" tab=SettingsTab::Audio active=tab search=search>
+                <Slider
+                    label="Playback rate"
+                    value=playback_rate
+                    default=DEFAULT_PLAYBACK_RATE
+                    min=0.15
+                    max=2.0
+                />
+            </Searchable>
+            <Searchable label="Pitch variation" tab=SettingsTab::Audio active=tab search=search>
+                <Slider
+                    label="Pitch variation"
+                    value=pitch_variation
+                    default=DEFAULT_PITCH_VARIATION
+                    min=0.0
+                    max=MAX_PITCH_VARIATION
+                />
+            </Searchable>
+            <Searchable label="Volume" tab=SettingsTab::Audio active=tab search=search>
+                <Slider
+                    label="Volume"
+                    value=volume
+                    default=DEFAULT_VOLUME
+                    min=0.01
+                    max=1.0
+                    muted=muted
+                />
+            </Searchable>
+            <Searchable label="Intensity curve" tab=SettingsTab::Audio active=tab search=search>
+                <CurveEditor
+                    label="Intensity curve"
+                    value=intensity_curve
+                    default=DEFAULT_INTENSITY_CURVE
+                />
+            </Searchable>
+            <Searchable label="Loop gap" tab=SettingsTab::Audio active=tab search=search>
+                <Slider
+                    label="Loop gap"
+                    value=loop_gap
+                    default=DEFAULT_LOOP_GAP
+                    min=0.0
+                    max=MAX_LOOP_GAP
+                />
+                <p class="settings-hint">
+                    {move || format!("{:.1} seconds between sounds in loop mode.", loop_gap.get())}
+                </p>
+            </Searchable>
+            <Searchable
+                label="Effects"
+                keywords=&["Reverb", "Echo"]
+                tab=SettingsTab::Audio
+                active=tab
+                search=search
+            >
+                <Toggle label="Reverb" value=reverb/>
+                <Toggle label="Echo" value=echo/>
+            </Searchable>
+            <Searchable
+                label="Low power mode"
+                tab=SettingsTab::Appearance
+                active=tab
+                search=search
+            >
+                <Toggle label="Low power mode" value=low_power/>
+                <p class="settings-hint">
+                    "Hides the visualizer, skips prefetching images and pauses the build bridge. "
+                    "Turns on by itself while the battery is low."
+                </p>
+            </Searchable>
+            <Searchable
+                label="Read aloud"
+                tab=SettingsTab::Appearance
+                active=tab
+                search=search
+            >
+                <Toggle
+                    label="Read aloud"
+                    value=read_aloud
+                    unsupported=unsupported(capabilities.speech, "speech synthesis")
+                />
+                <p class="settings-hint">
+                    "Speaks the name of every focused button and field, for browsers where the "
+                    "screen reader falls short."
+                </p>
+            </Searchable>
+            <Searchable
+                label="Microphone mode"
+                keywords=&["Interject on pauses", "Pause length"]
+                tab=SettingsTab::Integrations
+                active=tab
+                search=search
+            >
+                <Toggle
+                    label="Microphone mode"
+                    value=microphone
+                    unsupported=unsupported(
+                        capabilities.microphone && capabilities.web_audio,
+                        "microphone input",
+                    )
+                />
+                <Toggle label="Interject on pauses" value=interject/>
+                <Slider
+                    label="Pause length"
+                    value=interject_pause
+                    default=DEFAULT_INTERJECT_PAUSE
+                    min=1.0
+                    max=MAX_INTERJECT_PAUSE
+                />
+                <p class="settings-hint">
+                    {move || {
+                        format!(
+                            "Listens while you explain your problem, and quacks when you stop \
+                             talking for {:.1} seconds. Nothing is recorded or sent anywhere.",
+                            interject_pause.get(),
+                        )
+                    }}
+                </p>
+            </Searchable>
+            <Searchable
+                label="Meeting mode"
+                keywords=&["In a meeting", "Meeting hours", "From hour", "Until hour"]
+                tab=SettingsTab::Integrations
+                active=tab
+                search=search
+            >
+                <Toggle label="In a meeting" value=meeting/>
+                <Toggle label="Meeting hours" value=meeting_hours/>
+                <NumberInput
+                    label="From hour"
+                    value=meeting_start
+                    default=DEFAULT_MEETING_START
+                    min=0
+                    max=23
+                />
+                <NumberInput
+                    label="Until hour"
+                    value=meeting_end
+                    default=DEFAULT_MEETING_END
+                    min=0
+                    max=23
+                />
+                <p class="settings-hint">
+                    "Pauses the loop, the sequencer and the build bridge, while in a meeting or "
+                    "during the daily meeting hours. Playing a sound by hand still works."
+                </p>
+                <p class="settings-hint" class:hidden=move || !in_meeting.get()>
+                    "🤫 Automatic sounds are paused right now."
+                </p>
+            </Searchable>
+            <Searchable label="Latency calibration" tab=SettingsTab::Audio active=tab search=search>
+                <LatencyCalibration
+                    latency=latency
+                    default=DEFAULT_LATENCY
+                    sounds=pack_sounds
+                    quack=quack
+                />
+                <p class="settings-hint">
+                    "Delay until a sound is heard on this device. The sequencer starts its sounds "
+                    "early by as much, so they land on the beat."
+                </p>
+            </Searchable>
+            <Searchable label="Safety limiter" tab=SettingsTab::Audio active=tab search=search>
+                <Toggle label="Safety limiter" value=limiter/>
+            </Searchable>
+            <Searchable label="Volume boost" tab=SettingsTab::Audio active=tab search=search>
+                <Slider
+                    label="Volume boost"
+                    value=boost
+                    default=DEFAULT_BOOST
+                    min=1.0
+                    max=MAX_BOOST
+                />
+                <p class="settings-warning" class:hidden=move || boost.get() <= DEFAULT_BOOST>
+                    {move || format!("⚠️ Output is amplified {:.1}×. ", boost.get())}
+                    "It can be very loud and "
+                    "resets when the page is reloaded."
+                </p>
+            </Searchable>
+            <Searchable label="Overlapping sounds" tab=SettingsTab::Audio active=tab search=search>
+                <NumberInput
+                    label="Overlapping sounds"
+                    value=max_voices
+                    default=DEFAULT_MAX_VOICES
+                    min=1
+                    max=MAX_VOICES
+                />
+                <p class="settings-hint">
+                    "How many sounds can play at once, before the oldest one is cut off."
+                </p>
+            </Searchable>
+            <Searchable label="Sound pack" tab=SettingsTab::Sounds active=tab search=search>
+                <label class="slider">
+                    <span class="w-32">"Sound pack"</span>
+                    <select
+                        class="grow settings-select"
+                        on:change=move |event| sound_pack.set(event_target_value(&event))
+                    >
+                        <For
+                            each=move || sound_packs.get()
+                            key=|pack| pack.id.clone()
+                            children=pack_option
+                        />
+                    </select>
+                </label>
+                <p class="settings-hint">
+                    "Extra packs can be listed in " <code>"audio/packs.json"</code> "."
+                </p>
+            </Searchable>
+            <Searchable
+                label="Sound weights"
+                keywords=&["Favorites", "Disabled sounds"]
+                tab=SettingsTab::Sounds
+                active=tab
+                search=search
+            >
+                <p class="settings-hint">
+                    "Unchecked sounds are left out of the rotation, and sounds with a higher "
+                    "weight play more often, while a weight of 0 never plays. Starred favorites "
+                    "count twice."
+                </p>
+                <p class="settings-hint">
+                    {move || {
+                        format!(
+                            "Dimmed sounds aren't in the voice of {}, so they only play when \
+                             picked by hand.",
+                            selection.get().name(),
+                        )
+                    }}
+                </p>
+                <p
+                    class="settings-warning"
+                    class:hidden=move || {
+                        disabled_sounds
+                            .with(|disabled| {
+                                sounds()
+                                .iter()
+                                .any(|sound| !disabled.contains(sound.name))
+                            })
+                    }
+                >
+                    "⚠️ All sounds are disabled, so any of them may play."
+                </p>
+                <For
+                    each=sounds
+                    key=|sound| sound.name
+                    children=sound_view
+                />
+            </Searchable>
+            <Searchable
+                label="Custom sounds"
+                keywords=&["Add sounds", "Record"]
+                tab=SettingsTab::Sounds
+                active=tab
+                search=search
+            >
+                <label
+                    class="slider"
+                    class:opacity-50=!capabilities.indexed_db
+                    title=unsupported(capabilities.indexed_db, "IndexedDB")
+                >
+                    <span class="w-32">"Add sounds"</span>
+                    <input
+                        class="grow"
+                        type="file"
+                        disabled=!capabilities.indexed_db
+                        accept="audio/*"
+                        multiple
+                        on:change=upload
+                    />
+                </label>
+                <p class="settings-warning" class:hidden=move || rejected.with(Vec::is_empty)>
+                    {move || format!("⚠️ Skipped {}.", rejected.get().join(", "))}
+                </p>
+                <Recorder custom_sounds=custom_sounds/>
+                <p class="settings-hint">
+                    "Added sounds are kept in this browser and join the rotation like the "
+                    "built-in ones."
+                </p>
+            </Searchable>
+            <Searchable
+                label="Keyboard shortcuts"
+                tab=SettingsTab::Shortcuts
+                active=tab
+                search=search
+            >
+                <p class="settings-hint">
+                    "Click a shortcut and press its new key, or Escape to keep the current one."
+                </p>
+                <For each=Action::iter key=|action| *action children=shortcut_view/>
+                <button
+                    class="btn self-end py-0.5 px-1"
+                    on:click=move |_| keybindings.set(default_keybindings())
+                >
+                    "Reset shortcuts"
+                </button>
+            </Searchable>
+            <Searchable
+                label="Build bridge"
+                keywords=&["Bridge port"]
+                tab=SettingsTab::Integrations
+                active=tab
+                search=search
+            >
+                <Toggle
+                    label="Build bridge"
+                    value=bridge
+                    unsupported=unsupported(capabilities.websocket, "WebSockets")
+                />
+                <NumberInput
+                    label="Bridge port"
+                    value=bridge_port
+                    default=DEFAULT_BRIDGE_PORT
+                    min=1
+                    max=u16::MAX
+                />
+                <p class="settings-hint">
+                    "Quack for every message received on " <code>"ws://localhost:<port>"</code>
+                    ", for example after a green build."
+                </p>
+            </Searchable>
+            <Searchable label="Diagnostics" tab=SettingsTab::Data active=tab search=search>
+                <CopyDiagnostics/>
+            </Searchable>
+            <Searchable label="Recent errors" tab=SettingsTab::Data active=tab search=search>
+                <ErrorLog visible=Signal::derive(move || {
+                    show.get() && tab.get() == SettingsTab::Data
+                })/>
+            </Searchable>
+            <Searchable label="Ducks" tab=SettingsTab::Ducks active=tab search=search>
+                <p class="mb-2 font-bold">"Pick your duck!"</p>
+                <div class="settings-ducks" role="radiogroup" aria-label="Ducks">
+                    <For each=Duck::iter key=|duck| *duck children=duck_view/>
+                </div>
+                <p class="sr-only" aria-live="polite">
+                    {move || format!("{} selected", selection.get().name())}
+                </p>
+            </Searchable>
+            <Searchable label="Duck voices" tab=SettingsTab::Ducks active=tab search=search>
+                <p class="settings-hint">
+                    "Every duck quacks with its own sounds of the pack, as listed in "
+                    <code>"assets.json"</code> ". Custom sounds are shared by all ducks."
+                </p>
+                <For each=Duck::iter key=|duck| *duck children=voice_view/>
+            </Searchable>
+            <Searchable
+                label="Duck speech"
+                keywords=&["Voice", "Speech pitch", "Speech rate"]
+                tab=SettingsTab::Ducks
+                active=tab
+                search=search
+            >
+                <label
+                    class="slider"
+                    class:opacity-50=!capabilities.speech
+                    title=unsupported(capabilities.speech, "speech synthesis")
+                >
+                    <span class="w-32">"Voice"</span>
+                    <select
+                        class="grow settings-select"
+                        disabled=!capabilities.speech
+                        on:change=move |event| speech_voice.set(event_target_value(&event))
+                    >
+                        <option value="" prop:selected=move || speech_voice.with(String::is_empty)>
+                            "Default"
+                        </option>
+                        <For each=move || voices.get() key=String::clone children=voice_option/>
+                    </select>
+                </label>
+                <Slider
+                    label="Speech pitch"
+                    value=speech_pitch
+                    default=DEFAULT_SPEECH_PITCH
+                    min=0.0
+                    max=2.0
+                />
+                <Slider
+                    label="Speech rate"
+                    value=speech_rate
+                    default=DEFAULT_SPEECH_RATE
+                    min=0.5
+                    max=2.0
+                />
+                <p class="settings-hint">"How the duck sounds when it talks back to you."</p>
+            </Searchable>
+            <button class="btn p-2" on:click=close>
+                "Close"
+            </button>
+        </Dialog>
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub enum SettingsTab {
+    Audio,
+    Sounds,
+    Ducks,
+    Appearance,
+    Shortcuts,
+    Integrations,
+    Data,
+}
+
+impl SettingsTab {
+    pub fn iter() -> impl IntoIterator<Item = Self> {
+        [
+            Self::Audio,
+            Self::Sounds,
+            Self::Ducks,
+            Self::Appearance,
+            Self::Shortcuts,
+            Self::Integrations,
+            Self::Data,
+        ]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Audio => "Audio",
+            Self::Sounds => "Sounds",
+            Self::Ducks => "Ducks",
+            Self::Appearance => "Appearance",
+            Self::Shortcuts => "Shortcuts",
+            Self::Integrations => "Integrations",
+            Self::Data => "Data",
+        }
+    }
+
+    pub fn anchor(self) -> &'static str {
+        match self {
+            Self::Audio => "audio",
+            Self::Sounds => "sounds",
+            Self::Ducks => "ducks",
+            Self::Appearance => "appearance",
+            Self::Shortcuts => "shortcuts",
+            Self::Integrations => "integrations",
+            Self::Data => "data",
+        }
+    }
+
+    /// Parse a URL hash like `#settings/ducks`. A plain `#settings` opens the first tab, and
+    /// anything else doesn't point to the settings at all.
+    pub fn from_hash(hash: &str) -> Option<Self> {
+        match hash.strip_prefix("#settings")? {
+            "" | "/" => Some(Self::Audio),
+            anchor => Self::iter()
+                .into_iter()
+                .find(|tab| anchor.strip_prefix('/') == Some(tab.anchor())),
+        }
+    }
+}
+
+/// Settings entry that is only shown on its own tab. While searching, tabs are ignored and the
+/// entry is shown whenever the search fuzzy-matches its label or one of its keywords.
+#[component]
+pub fn searchable(
+    label: &'static str,
+    /// Labels of the single controls in the entry, that can be searched for as well.
+    #[prop(optional)]
+    keywords: &'static [&'static str],
+    tab: SettingsTab,
+    #[prop(into)] active: Signal<SettingsTab>,
+    #[prop(into)] search: Signal<String>,
+    children: Children,
+) -> impl IntoView {
+    let visible = move || {
+        let search = search.get();
+        if search.is_empty() {
+            active.get() == tab
+        } else {
+            iter::once(label)
+                .chain(keywords.iter().copied())
+                .any(|label| fuzzy_match(&search, label))
+        }
+    };
+
+    view! { <div class:hidden=move || !visible()>{children()}</div> }
+}
+
+/// List of the errors in the diagnostics buffer, refreshed whenever it becomes `visible`.
+#[component]
+pub fn error_log(visible: Signal<bool>) -> impl IntoView {
+    let errors = move || {
+        visible.track();
+        recent_errors()
+    };
+
+    view! {
+        <div class="settings-errors">
+            <Show
+                when=move || !errors().is_empty()
+                fallback=|| view! { <p class="text-slate-400">"No errors so far 🎉"</p> }
+            >
+                <ol>
+                    {move || {
+                        errors()
+                            .into_iter()
+                            .rev()
+                            .map(|error| {
+                                view! {
+                                    <li>
+                                        <span class="text-slate-400">{error.time}</span>
+                                        " "
+                                        <code>{error.source}</code>
+                                        <pre>{error.message}</pre>
+                                    </li>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </ol>
+            </Show>
+        </div>
+    }
+}
+
+/// Check whether all characters of the `query` appear in the `label` in the same order, ignoring
+/// case and whitespace. An empty query matches everything.
+pub fn fuzzy_match(query: &str, label: &str) -> bool {
+    let mut label = label.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .all(|q| label.any(|l| l == q))
+}
+
+/// Button that copies a [`Diagnostics`] report to the clipboard, to attach to bug reports.
+#[component]
+pub fn copy_diagnostics() -> impl IntoView {
+    let capabilities = Capabilities::get();
+    let status = create_rw_signal("");
+
+    let copy = move |_| {
+        spawn_local(async move {
+            let report = serde_json::to_string_pretty(&Diagnostics::collect()).unwrap_throw();
+            match JsFuture::from(write_clipboard(&report)).await {
+                Ok(_) => status.set("Copied to the clipboard"),
+                Err(e) => {
+                    report_error("settings", format!("failed copying diagnostics:\n{e:?}"));
+                    status.set("Copying failed");
+                }
+            }
+        });
+    };
+
+    view! {
+        <div class="slider">
+            <span class="w-32">"Diagnostics"</span>
+            <button
+                class="btn py-0.5 px-1"
+                disabled=!capabilities.clipboard
+                title=unsupported(capabilities.clipboard, "the clipboard")
+                on:click=copy
+            >
+                "Copy diagnostic state"
+            </button>
+            <span class="text-slate-400" aria-live="polite">
+                {status}
+            </span>
+        </div>
+        <p class="settings-hint">
+            "Contains versions, settings, audio support and recent errors, but nothing personal."
+        </p>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_match_in_order() {
+        assert!(fuzzy_match("", "Volume"));
+        assert!(fuzzy_match("vol", "Volume"));
+        assert!(fuzzy_match("VLM", "Volume"));
+        assert!(fuzzy_match("pb rate", "Playback rate"));
+        assert!(!fuzzy_match("emulov", "Volume"));
+        assert!(!fuzzy_match("volumes", "Volume"));
+    }
+
+    #[test]
+    fn settings_tab_from_hash() {
+        assert_eq!(
+            SettingsTab::from_hash("#settings"),
+            Some(SettingsTab::Audio)
+        );
+        assert_eq!(
+            SettingsTab::from_hash("#settings/"),
+            Some(SettingsTab::Audio)
+        );
+        assert_eq!(
+            SettingsTab::from_hash("#settings/ducks"),
+            Some(SettingsTab::Ducks)
+        );
+        assert_eq!(SettingsTab::from_hash("#settings/unknown"), None);
+        assert_eq!(SettingsTab::from_hash("#settingsducks"), None);
+        assert_eq!(SettingsTab::from_hash("#sound=duck5"), None);
+        assert_eq!(SettingsTab::from_hash(""), None);
+    }
+
+    #[test]
+    fn settings_tab_anchors_round_trip() {
+        for tab in SettingsTab::iter() {
+            let hash = format!("#settings/{}", tab.anchor());
+            assert_eq!(SettingsTab::from_hash(&hash), Some(tab));
+        }
+    }
+}
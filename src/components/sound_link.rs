@@ -0,0 +1,139 @@
+use leptos::{
+    component, ev, prelude::*, spawn_local, view, window, window_event_listener, IntoView,
+};
+use wasm_bindgen::{JsValue, UnwrapThrowExt};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{
+    audio::{all_sounds, Sound},
+    diagnostics::{report_error, write_clipboard},
+};
+
+/// Asks to play the sound from a `#sound=<name>` link. Browsers only allow playback after a user
+/// gesture, so a link can't play its sound right away.
+#[component]
+pub fn sound_link(
+    #[prop(into)] pack_sounds: Signal<Vec<Sound>>,
+    #[prop(into)] custom_sounds: Signal<Vec<Sound>>,
+    #[prop(into)] quack: SignalSetter<Option<Sound>>,
+) -> impl IntoView {
+    let linked = create_rw_signal(None::<String>);
+    let missing = create_rw_signal(false);
+
+    let open_from_hash = move || {
+        if let Some(name) = from_hash(&window().location().hash().unwrap_throw()) {
+            linked.set(Some(name));
+            missing.set(false);
+        }
+    };
+    open_from_hash();
+    // Lives as long as the app.
+    window_event_listener(ev::hashchange, move |_| open_from_hash());
+
+    let close = move || {
+        linked.set(None);
+        let location = window().location();
+        let url = format!(
+            "{}{}",
+            location.pathname().unwrap_throw(),
+            location.search().unwrap_throw()
+        );
+        window()
+            .history()
+            .unwrap_throw()
+            .replace_state_with_url(&JsValue::NULL, "", Some(&url))
+            .unwrap_throw();
+    };
+    let play = move |_| {
+        let name = linked.get_untracked().unwrap_or_default();
+        // Looked up only now, as custom sounds and packs load in the background.
+        let sound = pack_sounds.with_untracked(|pack| {
+            custom_sounds.with_untracked(|custom| {
+                all_sounds(pack, custom)
+                    .into_iter()
+                    .find(|sound| sound.name == name)
+            })
+        });
+
+        match sound {
+            Some(sound) => {
+                quack.set(Some(sound));
+                close();
+            }
+            None => missing.set(true),
+        }
+    };
+
+    view! {
+        <div
+            class="wake-overlay"
+            class:hidden=move || linked.with(Option::is_none)
+            role="dialog"
+            aria-label="Shared sound"
+        >
+            <span class="text-6xl">"🦆📨"</span>
+            <span class="text-2xl">
+                {move || format!("Someone sent you a quack: {}", linked.get().unwrap_or_default())}
+            </span>
+            <p class="settings-warning" class:hidden=move || !missing.get()>
+                "⚠️ This sound isn't available here, maybe it's from another sound pack."
+            </p>
+            <div class="flex gap-2">
+                <button class="btn py-1 px-2" on:click=play>
+                    "🔊 Play it"
+                </button>
+                <button class="btn py-1 px-2" on:click=move |_| close()>
+                    "Dismiss"
+                </button>
+            </div>
+        </div>
+    }
+}
+
+/// Copy a link to the clipboard, that offers to play the `sound` when opened.
+pub fn copy_sound_link(sound: Sound) {
+    let location = window().location();
+    let link = format!(
+        "{}{}#sound={}",
+        location.origin().unwrap_throw(),
+        location.pathname().unwrap_throw(),
+        js_sys::encode_uri_component(sound.name)
+    );
+
+    spawn_local(async move {
+        if let Err(e) = JsFuture::from(write_clipboard(&link)).await {
+            report_error("sound_link", format!("failed copying sound link:\n{e:?}"));
+        }
+    });
+}
+
+/// Parse a URL hash like `#sound=duck5`, with the name of the sound percent-encoded.
+fn from_hash(hash: &str) -> Option<String> {
+    js_sys::decode_uri_component(encoded_name(hash)?)
+        .ok()?
+        .as_string()
+}
+
+/// The still percent-encoded sound name of a `#sound=<name>` hash, if it has one.
+fn encoded_name(hash: &str) -> Option<&str> {
+    hash.strip_prefix("#sound=").filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoded_name_of_sound_hash() {
+        assert_eq!(encoded_name("#sound=duck5"), Some("duck5"));
+        assert_eq!(encoded_name("#sound=my%20quack"), Some("my%20quack"));
+    }
+
+    #[test]
+    fn encoded_name_rejects_other_hashes() {
+        assert_eq!(encoded_name(""), None);
+        assert_eq!(encoded_name("#settings/sounds"), None);
+        assert_eq!(encoded_name("#sound="), None);
+        assert_eq!(encoded_name("#sounds=duck5"), None);
+    }
+}
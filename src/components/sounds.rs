@@ -0,0 +1,488 @@
+use std::{cell::RefCell, iter, rc::Rc, time::Duration};
+
+use leptos::{
+    component, create_effect, prelude::*, spawn_local, store_value, untrack, view, window,
+    IntoView, Show,
+};
+use log::debug;
+use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{HtmlAudioElement, PointerEvent};
+
+use super::visualizer::Visualizer;
+use crate::{
+    audio::{
+        all_sounds, bundled_quack, choose_sound, fragment_duration, get_or_create_graph,
+        is_not_allowed, load_sprite, pick_voice, resume, weighted_pool, CurvePoint, DisabledSounds,
+        FavoriteSounds, SharedGraph, ShuffleBag, Sound, SoundWeights, Voice,
+    },
+    capabilities::{unsupported, Capabilities},
+    commands::register_command,
+    diagnostics::report_error,
+    ducks::{prefetch_images_when_idle, Duck},
+    media_session::{create_media_session, show_playing, show_stopped},
+    time::sleep,
+};
+
+#[component]
+pub fn sounds(
+    playback_rate: Signal<f64>,
+    volume: Signal<f64>,
+    intensity_curve: Signal<Vec<CurvePoint>>,
+    limiter: Signal<bool>,
+    boost: Signal<f64>,
+    max_voices: Signal<u8>,
+    pitch_variation: Signal<f64>,
+    reverb: Signal<bool>,
+    echo: Signal<bool>,
+    saving_power: Signal<bool>,
+    /// Hold back sounds that play by themselves, like the loop.
+    in_meeting: Signal<bool>,
+    /// Name of the key that plays a sound, if any.
+    play_key: Signal<Option<String>>,
+    /// Pause between sounds in loop mode, in seconds.
+    loop_gap: Signal<f64>,
+    sound_weights: Signal<SoundWeights>,
+    disabled_sounds: Signal<DisabledSounds>,
+    favorite_sounds: Signal<FavoriteSounds>,
+    /// Sounds of the selected pack.
+    pack_sounds: Signal<Vec<Sound>>,
+    /// Selected duck, whose voice narrows down the sounds of the pack.
+    duck: Signal<Duck>,
+    custom_sounds: Signal<Vec<Sound>>,
+    /// Audio graph that the sounds are played through, shared with the sequencer.
+    graph: SharedGraph,
+    #[prop(into)] quack: Signal<Option<Sound>>,
+    /// Leave out the loop, that keeps quacking without anyone asking for it.
+    classroom: bool,
+) -> impl IntoView {
+    let voices = Rc::new(RefCell::new(Vec::<Rc<Voice>>::new()));
+
+    // Also run once the graph is created, wherever that happens.
+    create_effect(move |_| {
+        let limiter = limiter.get();
+        if let Some(graph) = graph.get() {
+            graph.set_limiter(limiter);
+        }
+    });
+
+    create_effect(move |_| {
+        let boost = boost.get();
+        if let Some(graph) = graph.get() {
+            graph.set_boost(boost);
+        }
+    });
+
+    create_effect(move |_| {
+        let (reverb, echo) = (reverb.get(), echo.get());
+        if let Some(graph) = graph.get() {
+            graph.set_reverb(reverb);
+            graph.set_echo(echo);
+        }
+    });
+
+    create_effect({
+        let voices = Rc::clone(&voices);
+        move |_| {
+            let (playback_rate, volume) = (playback_rate.get(), volume.get());
+            for voice in voices.borrow().iter() {
+                voice.audio.set_default_playback_rate(playback_rate);
+                voice.audio.set_volume(volume);
+            }
+        }
+    });
+
+    // The sprite has to be known first, as sounds in it don't need to be preloaded.
+    let sprite_loaded = create_rw_signal(false);
+    spawn_local(async move {
+        match load_sprite().await {
+            Ok(true) => debug!("loaded audio sprite"),
+            Ok(false) => {}
+            Err(e) => report_error("sounds", format!("failed loading the audio sprite:\n{e:?}")),
+        }
+        sprite_loaded.set(true);
+    });
+
+    let preloaded = create_rw_signal(0);
+    // Bumped whenever the pack changes, so preloads of the previous pack don't count anymore.
+    let preload_run = store_value(0_u32);
+    create_effect(move |previous: Option<Vec<Sound>>| {
+        if !sprite_loaded.get() {
+            return Vec::new();
+        }
+        let sounds = pack_sounds.get();
+
+        // Keep the memory of sounds in the previous pack only if the new pack shares them.
+        for sound in previous
+            .iter()
+            .flatten()
+            .filter(|sound| !sounds.contains(sound))
+        {
+            sound.forget();
+        }
+
+        preload_run.update_value(|run| *run += 1);
+        let run = preload_run.get_value();
+        preloaded.set(0);
+        let audio = HtmlAudioElement::new().unwrap_throw();
+        for sound in sounds.iter().copied() {
+            let audio = audio.clone();
+            spawn_local(async move {
+                if let Err(e) = sound.preload(&audio).await {
+                    report_error(
+                        "sounds",
+                        format!("failed preloading sound `{}`:\n{e:?}", sound.name),
+                    );
+                }
+                if preload_run.get_value() == run {
+                    preloaded.update(|count| *count += 1);
+                }
+            });
+        }
+
+        sounds
+    });
+
+    if !saving_power.get_untracked() {
+        prefetch_images_when_idle({
+            let voices = Rc::clone(&voices);
+            move || voices.borrow().iter().any(|voice| !voice.is_idle())
+        });
+    }
+
+    let bag = create_rw_signal(ShuffleBag::default());
+
+    // Set when the browser blocked playback, until the user explicitly wakes the duck.
+    let asleep = create_rw_signal(false);
+
+    let web_audio = Capabilities::get().web_audio;
+
+    // For the media keys, which can stop the sounds from outside.
+    let playing_voices = Rc::clone(&voices);
+
+    // Resolves to the length of the sound in seconds, once it started playing.
+    let play_sound = move |sound: Option<Sound>| {
+        /// Time after the expected end of a sound, until it surely finished.
+        const ENDED_MARGIN: Duration = Duration::from_millis(100);
+
+        let voices = Rc::clone(&voices);
+        async move {
+            // Every other way of playing a sound ends up here, so they don't need to check.
+            if !web_audio {
+                return None;
+            }
+
+            let graph = get_or_create_graph(graph);
+            if !resume(&graph).await {
+                debug!("audio context is still suspended");
+                asleep.set(true);
+                return None;
+            }
+
+            let sound = sound.or_else(|| {
+                bag.try_update(|bag| {
+                    let sounds = custom_sounds.with_untracked(|custom| {
+                        disabled_sounds.with_untracked(|disabled| {
+                            pack_sounds.with_untracked(|pack| {
+                                duck.get_untracked().playable(pack, custom, disabled)
+                            })
+                        })
+                    });
+                    bag.draw(&sound_weights.with_untracked(|weights| {
+                        favorite_sounds
+                            .with_untracked(|favorites| weighted_pool(&sounds, weights, favorites))
+                    }))
+                })
+                .flatten()
+            });
+            let Some(sound) = sound else {
+                report_error("sounds", "no sound to pick from".to_owned());
+                return None;
+            };
+            let voice = pick_voice(
+                &graph,
+                &mut voices.borrow_mut(),
+                max_voices.get_untracked().into(),
+            );
+            let audio = &voice.audio;
+            // Loading a source resets the rate to the default one, so that is where the random
+            // offset goes, to keep repeated sounds from sounding identical.
+            let variation = pitch_variation.get_untracked() * (fastrand::f64() * 2.0 - 1.0);
+            audio.set_default_playback_rate(playback_rate.get_untracked() * (1.0 + variation));
+            audio.set_volume(volume.get_untracked());
+
+            // The browser may claim support for a format and still fail decoding it, so fall back
+            // to the next format in that case. As last resort, for example when the sound files
+            // can't be loaded, play the quack that is bundled with the app.
+            for src in sound.sources(audio).chain(iter::once_with(bundled_quack)) {
+                audio.set_src(&src);
+                match JsFuture::from(audio.play().unwrap_throw()).await {
+                    Ok(_) => {
+                        show_playing(sound, duck.get_untracked());
+                        let duration = fragment_duration(&src).unwrap_or_else(|| audio.duration())
+                            / audio.playback_rate();
+                        graph.apply_curve(&voice, &intensity_curve.get_untracked(), duration);
+                        if duration.is_finite() {
+                            let voices = Rc::clone(&voices);
+                            spawn_local(async move {
+                                sleep(Duration::from_secs_f64(duration) + ENDED_MARGIN).await;
+                                if voices.borrow().iter().all(|voice| voice.is_idle()) {
+                                    show_stopped();
+                                }
+                            });
+                        }
+                        return Some(duration);
+                    }
+                    Err(e) if is_not_allowed(&e) => {
+                        debug!("playback was blocked by the browser");
+                        asleep.set(true);
+                        return None;
+                    }
+                    Err(e) => report_error("sounds", format!("failed playing `{src}`:\n{e:?}")),
+                }
+            }
+
+            report_error(
+                "sounds",
+                format!("no playable source for sound `{}`", sound.name),
+            );
+            None
+        }
+    };
+
+    let play = {
+        let play_sound = play_sound.clone();
+        move |sound: Option<Sound>| {
+            let playing = play_sound(sound);
+            spawn_local(async move {
+                playing.await;
+            });
+        }
+    };
+
+    // Holding the play button fires quacks at an increasing rate. Like the loop, every press bumps
+    // the counter, so the repeats of a released press stop.
+    let hold_run = store_value(0_u32);
+    let held = store_value(false);
+    let hold = {
+        let play = play.clone();
+        move |event: PointerEvent| {
+            /// Wait before a press counts as holding, and the first gap between quacks.
+            const DELAY: Duration = Duration::from_millis(400);
+            const MIN_GAP: Duration = Duration::from_millis(80);
+
+            // Only the primary button, so a right click doesn't start quacking.
+            if event.button() != 0 {
+                return;
+            }
+
+            hold_run.update_value(|run| *run += 1);
+            held.set_value(false);
+            let run = hold_run.get_value();
+            let play = play.clone();
+            spawn_local(async move {
+                let mut gap = DELAY;
+                loop {
+                    sleep(gap).await;
+                    if hold_run.get_value() != run {
+                        break;
+                    }
+                    held.set_value(true);
+                    play(None);
+                    vibrate();
+                    gap = gap.mul_f64(0.8).max(MIN_GAP);
+                }
+            });
+        }
+    };
+    let release = move |_| hold_run.update_value(|run| *run += 1);
+    let click = {
+        let play = play.clone();
+        move |_| {
+            // The press already quacked while being held, so releasing it shouldn't again.
+            if !held.get_value() {
+                play(None);
+            }
+            held.set_value(false);
+        }
+    };
+
+    // Favorites of all available sounds, regardless of the duck's voice, as they were picked by
+    // hand.
+    let favorites = move || {
+        custom_sounds.with(|custom| {
+            pack_sounds.with(|pack| {
+                favorite_sounds.with(|favorites| {
+                    all_sounds(pack, custom)
+                        .into_iter()
+                        .filter(|sound| favorites.contains(sound.name))
+                        .collect::<Vec<_>>()
+                })
+            })
+        })
+    };
+    let play_favorite = {
+        let play = play.clone();
+        move || {
+            if let Some(sound) = choose_sound(untrack(favorites)) {
+                play(Some(sound));
+            }
+        }
+    };
+    register_command("Play a favorite sound", play_favorite.clone());
+
+    let looping = create_rw_signal(false);
+    // Bumped whenever the loop starts or stops, so a loop that is still waiting for its next sound
+    // notices that it was replaced.
+    let loop_run = store_value(0_u32);
+    let toggle_loop = move || {
+        loop_run.update_value(|run| *run += 1);
+        if looping.get_untracked() {
+            looping.set(false);
+            return;
+        }
+
+        looping.set(true);
+        let run = loop_run.get_value();
+        let play_sound = play_sound.clone();
+        spawn_local(async move {
+            // Shortest wait between rounds, so a loop that plays nothing, like during a meeting
+            // with no gap set, doesn't spin on the timer.
+            const MIN_WAIT: Duration = Duration::from_millis(500);
+
+            while loop_run.get_value() == run {
+                // Keeps going without sound during meetings, to pick up again right after.
+                let duration = if in_meeting.get_untracked() {
+                    0.0
+                } else if let Some(duration) = play_sound(None).await {
+                    duration
+                } else {
+                    looping.set(false);
+                    break;
+                };
+                let duration = if duration.is_finite() { duration } else { 0.0 };
+                let wait = Duration::from_secs_f64(duration + loop_gap.get_untracked());
+                sleep(wait.max(MIN_WAIT)).await;
+            }
+        });
+    };
+
+    if !classroom {
+        register_command("Start or stop the loop", toggle_loop.clone());
+    }
+
+    create_media_session(
+        {
+            let play = play.clone();
+            move || play(None)
+        },
+        {
+            let toggle_loop = toggle_loop.clone();
+            move || {
+                if looping.get_untracked() {
+                    toggle_loop();
+                }
+                for voice in playing_voices.borrow().iter() {
+                    voice.audio.pause().ok();
+                }
+            }
+        },
+    );
+
+    create_effect({
+        let play = play.clone();
+        move |requested| {
+            let sound = quack.get();
+            if requested.is_some() {
+                play(sound);
+            }
+        }
+    });
+
+    let wake = {
+        let play = play.clone();
+        move |_| {
+            asleep.set(false);
+            play(None);
+        }
+    };
+
+    view! {
+        <Show when=move || !saving_power.get()>
+            <Visualizer graph=graph class="self-center mb-4"/>
+        </Show>
+        <button
+            id="play"
+            class="p-3 text-3xl bg-green-600 rounded-full border-2 border-green-700 transition-all hover:bg-green-700 hover:border-green-600 max-w-[400px] select-none"
+            on:click=click
+            on:pointerdown=hold
+            on:pointerup=release
+            on:pointercancel=release
+            on:pointerleave=release
+            on:contextmenu=|event| event.prevent_default()
+            disabled=!web_audio
+            title=unsupported(web_audio, "Web Audio")
+            aria-keyshortcuts=move || play_key.get()
+        >
+            "🔊 Play Sound"
+            <kbd class="block text-sm text-green-200" class:hidden=move || play_key.get().is_none()>
+                {move || play_key.get()}
+            </kbd>
+        </button>
+        {(!classroom)
+            .then(|| {
+                let toggle_loop = toggle_loop.clone();
+                view! {
+                    <button
+                        class="btn self-center py-1 px-2 mt-2"
+                        aria-pressed=move || looping.get().to_string()
+                        disabled=!web_audio
+                        on:click=move |_| toggle_loop()
+                    >
+                        {move || if looping.get() { "⏹ Stop loop" } else { "🔁 Loop" }}
+                    </button>
+                }
+            })}
+        <button
+            class="btn self-center py-1 px-2 mt-2"
+            disabled=move || !web_audio || favorites().is_empty()
+            title=move || {
+                if web_audio && favorites().is_empty() {
+                    Some("Star sounds in the settings to play them from here".to_owned())
+                } else {
+                    unsupported(web_audio, "Web Audio")
+                }
+            }
+            on:click=move |_| play_favorite()
+        >
+            "⭐ Play a favorite"
+        </button>
+        <span class="text-sm text-amber-200" class:hidden=move || !in_meeting.get() role="status">
+            "🤫 In a meeting, automatic sounds are paused"
+        </span>
+        <span
+            class="text-sm text-slate-400"
+            class:invisible=move || preloaded.get() == pack_sounds.with(Vec::len)
+            aria-live="polite"
+        >
+            {move || {
+                format!("Loading sounds… {}/{}", preloaded.get(), pack_sounds.with(Vec::len))
+            }}
+        </span>
+        <button class="wake-overlay" class:hidden=move || !asleep.get() on:click=wake>
+            <span class="text-6xl">"🦆💤"</span>
+            <span class="text-2xl">"Tap to wake the duck"</span>
+            <span class="text-slate-400">
+                "Your browser paused the sound until you interact with the page"
+            </span>
+        </button>
+    }
+}
+
+/// Short haptic feedback, on devices that support it.
+fn vibrate() {
+    let navigator = window().navigator();
+    if js_sys::Reflect::has(&navigator, &"vibrate".into()).unwrap_or_default() {
+        navigator.vibrate_with_duration(20);
+    }
+}
@@ -0,0 +1,59 @@
+use leptos::{component, event_target_value, prelude::*, view, IntoView};
+
+use crate::speech::speak;
+
+/// What the duck says when there is nothing to read back, in the spirit of rubber duck debugging.
+const RESPONSES: &[&str] = &[
+    "Quack. Start from the beginning, line by line.",
+    "What did you expect it to do, and what does it do instead?",
+    "Quack quack. Have you read the error message out loud?",
+    "Which of your assumptions haven't you checked yet?",
+];
+
+/// Text box for explaining a problem to the duck, which reads it back in its own voice.
+#[component]
+pub fn talk(
+    show: RwSignal<bool>,
+    /// Name of the speech synthesis voice, or empty for the browser's default.
+    #[prop(into)]
+    voice: Signal<String>,
+    #[prop(into)] pitch: Signal<f64>,
+    #[prop(into)] rate: Signal<f64>,
+) -> impl IntoView {
+    let text = create_rw_signal(String::new());
+
+    let answer = move |_| {
+        let problem = text.get_untracked();
+        let text = if problem.trim().is_empty() {
+            RESPONSES[fastrand::usize(..RESPONSES.len())]
+        } else {
+            &problem
+        };
+        speak(
+            text,
+            &voice.get_untracked(),
+            pitch.get_untracked(),
+            rate.get_untracked(),
+        );
+    };
+
+    view! {
+        <div class="talk" role="dialog" aria-label="Talk to the duck">
+            <textarea
+                class="talk-text"
+                rows="3"
+                placeholder="Explain your problem to the duck…"
+                prop:value=text
+                on:input=move |event| text.set(event_target_value(&event))
+            ></textarea>
+            <div class="flex gap-2">
+                <button class="btn py-1 px-2" on:click=answer>
+                    "🦆 Answer"
+                </button>
+                <button class="btn py-1 px-2" on:click=move |_| show.set(false)>
+                    "Close"
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,81 @@
+use leptos::{
+    component, ev, on_cleanup, prelude::*, view, window_event_listener, CollectView, IntoView,
+};
+use web_sys::KeyboardEvent;
+
+use crate::{audio::Sound, keys::is_typing};
+
+/// Home row keys, one for every sound, from left to right.
+const KEYS: [&str; 9] = ["a", "s", "d", "f", "g", "h", "j", "k", "l"];
+
+/// On-screen keyboard that turns the home row into a duck instrument, as long as it is shown.
+#[component]
+pub fn key_trainer(
+    show: RwSignal<bool>,
+    /// Sounds to map the keys to, as long as there are enough keys.
+    #[prop(into)]
+    sounds: Signal<Vec<Sound>>,
+    #[prop(into)] quack: SignalSetter<Option<Sound>>,
+) -> impl IntoView {
+    let pressed = create_rw_signal(None::<usize>);
+    let press = move |index: usize| {
+        pressed.set(Some(index));
+        if let Some(sound) = sounds.with_untracked(|sounds| sounds.get(index).copied()) {
+            quack.set(Some(sound));
+        }
+    };
+
+    let keydown = window_event_listener(ev::keydown, move |event: KeyboardEvent| {
+        if event.repeat()
+            || event.ctrl_key()
+            || event.alt_key()
+            || event.meta_key()
+            || is_typing(&event)
+        {
+            return;
+        }
+        if event.key() == "Escape" {
+            show.set(false);
+            return;
+        }
+        if let Some(index) = KEYS
+            .iter()
+            .position(|key| event.key().eq_ignore_ascii_case(key))
+        {
+            event.prevent_default();
+            press(index);
+        }
+    });
+    let keyup = window_event_listener(ev::keyup, move |_| pressed.set(None));
+    on_cleanup(move || {
+        keydown.remove();
+        keyup.remove();
+    });
+
+    let key_view = move |(index, (key, sound)): (usize, (&'static str, Sound))| {
+        view! {
+            <button
+                class="key-cap"
+                class:pressed=move || pressed.get() == Some(index)
+                on:pointerdown=move |_| press(index)
+                on:pointerup=move |_| pressed.set(None)
+            >
+                <kbd class="text-2xl uppercase">{key}</kbd>
+                <span class="text-xs text-slate-400">{sound.name}</span>
+            </button>
+        }
+    };
+
+    view! {
+        <div class="key-trainer" role="dialog" aria-label="Key trainer">
+            <div class="flex gap-1">
+                {move || {
+                    KEYS.into_iter().zip(sounds.get()).enumerate().map(key_view).collect_view()
+                }}
+            </div>
+            <button class="btn py-1 px-2" on:click=move |_| show.set(false)>
+                "Close"
+            </button>
+        </div>
+    }
+}
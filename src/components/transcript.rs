@@ -0,0 +1,140 @@
+use js_sys::Date;
+use leptos::{component, prelude::*, store_value, view, CollectView, IntoView};
+use wasm_bindgen::JsValue;
+
+use crate::{
+    diagnostics::report_error,
+    transcription::{
+        start_recognition, Recognition, RecognitionError, SavedTranscript, MAX_TRANSCRIPTS,
+    },
+};
+
+/// Live transcript of what the user tells the duck, that can be saved to the history.
+#[component]
+pub fn transcript(
+    show: RwSignal<bool>,
+    transcripts: RwSignal<Vec<SavedTranscript>>,
+) -> impl IntoView {
+    let text = create_rw_signal(String::new());
+    let listening = create_rw_signal(false);
+    let denied = create_rw_signal(false);
+    let recognition = store_value(None::<Recognition>);
+
+    let stop = move || {
+        recognition.set_value(None);
+        listening.set(false);
+    };
+    let start = move |_| {
+        denied.set(false);
+        // Results of a recognition always start from scratch, so earlier text is kept in front.
+        let before = text.get_untracked();
+        let joined = move |part: String| {
+            if before.is_empty() {
+                part
+            } else {
+                format!("{before} {part}")
+            }
+        };
+        match start_recognition(
+            move |part| text.set(joined(part)),
+            move |error| match error {
+                RecognitionError::NotAllowed => denied.set(true),
+                RecognitionError::Other(e) => {
+                    report_error("transcript", format!("speech recognition failed: {e}"));
+                }
+            },
+            move || listening.set(false),
+        ) {
+            Ok(started) => {
+                recognition.set_value(Some(started));
+                listening.set(true);
+            }
+            Err(e) => report_error("transcript", format!("failed starting recognition:\n{e:?}")),
+        }
+    };
+    let save = move |_| {
+        stop();
+        let text = text.get_untracked();
+        if text.is_empty() {
+            return;
+        }
+        transcripts.update(|transcripts| {
+            transcripts.push(SavedTranscript {
+                saved: Date::new_0().to_iso_string().into(),
+                text,
+            });
+            let excess = transcripts.len().saturating_sub(MAX_TRANSCRIPTS);
+            transcripts.drain(..excess);
+        });
+    };
+    let close = move |_| {
+        stop();
+        show.set(false);
+    };
+
+    let saved_view = move |(index, saved): (usize, SavedTranscript)| {
+        let time = Date::new(&saved.saved.into())
+            .to_locale_string("default", &JsValue::UNDEFINED)
+            .as_string()
+            .unwrap_or_default();
+        let remove = move |_| {
+            transcripts.update(|transcripts| {
+                transcripts.remove(index);
+            });
+        };
+        view! {
+            <li class="transcript-entry">
+                <span class="text-xs text-slate-400">{time}</span>
+                <span class="grow">{saved.text}</span>
+                <button class="btn py-0.5 px-1" aria-label="Delete transcript" on:click=remove>
+                    "🗑"
+                </button>
+            </li>
+        }
+    };
+
+    view! {
+        <div class="talk" role="dialog" aria-label="Transcript">
+            <p class="talk-text min-h-16 text-left" aria-live="polite">
+                {move || {
+                    let text = text.get();
+                    if text.is_empty() { "Talk through your problem…".to_owned() } else { text }
+                }}
+            </p>
+            <p class="settings-warning" class:hidden=move || !denied.get()>
+                "⚠️ Microphone access was denied."
+            </p>
+            <div class="flex gap-2">
+                {move || {
+                    if listening.get() {
+                        view! {
+                            <button class="btn py-1 px-2" on:click=move |_| stop()>
+                                "⏹ Stop"
+                            </button>
+                        }
+                    } else {
+                        view! {
+                            <button class="btn py-1 px-2" on:click=start>
+                                "🎤 Listen"
+                            </button>
+                        }
+                    }
+                }}
+                <button class="btn py-1 px-2" on:click=save>
+                    "Save"
+                </button>
+                <button class="btn py-1 px-2" on:click=move |_| text.set(String::new())>
+                    "Clear"
+                </button>
+                <button class="btn py-1 px-2" on:click=close>
+                    "Close"
+                </button>
+            </div>
+            <ul class="w-full" class:hidden=move || transcripts.with(Vec::is_empty)>
+                {move || {
+                    transcripts.get().into_iter().enumerate().rev().map(saved_view).collect_view()
+                }}
+            </ul>
+        </div>
+    }
+}
@@ -0,0 +1,95 @@
+use std::{cell::Cell, rc::Rc};
+
+use leptos::{
+    component, create_effect, create_node_ref, html, on_cleanup, prelude::*,
+    request_animation_frame, view, IntoView,
+};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{AnalyserNode, CanvasRenderingContext2d};
+
+use crate::audio::SharedGraph;
+
+/// Frequency bars of whatever the `graph` is currently playing, redrawn every frame. Stops
+/// redrawing once it fell silent, until the next sound.
+#[component]
+pub fn visualizer(graph: SharedGraph, #[prop(optional)] class: &'static str) -> impl IntoView {
+    const WIDTH: f64 = 256.0;
+    const HEIGHT: f64 = 48.0;
+    /// Frames without any sound, before the redrawing stops. Long enough to outlast the wait for a
+    /// scheduled sound to start.
+    const SILENT_FRAMES: u32 = 60;
+
+    let canvas = create_node_ref::<html::Canvas>();
+    let alive = Rc::new(Cell::new(true));
+    let running = Rc::new(Cell::new(false));
+    on_cleanup({
+        let alive = Rc::clone(&alive);
+        move || alive.set(false)
+    });
+
+    fn animate(
+        ctx: CanvasRenderingContext2d,
+        analyser: AnalyserNode,
+        alive: Rc<Cell<bool>>,
+        running: Rc<Cell<bool>>,
+        silent: u32,
+    ) {
+        request_animation_frame(move || {
+            if !alive.get() {
+                running.set(false);
+                return;
+            }
+
+            let mut data = vec![0; analyser.frequency_bin_count() as usize];
+            analyser.get_byte_frequency_data(&mut data);
+
+            ctx.clear_rect(0.0, 0.0, WIDTH, HEIGHT);
+            let width = WIDTH / data.len() as f64;
+            for (i, value) in data.iter().enumerate() {
+                let height = f64::from(*value) / 255.0 * HEIGHT;
+                ctx.fill_rect(i as f64 * width, HEIGHT - height, width - 1.0, height);
+            }
+
+            let silent = if data.iter().all(|&value| value == 0) {
+                silent + 1
+            } else {
+                0
+            };
+            if silent >= SILENT_FRAMES {
+                running.set(false);
+                return;
+            }
+
+            animate(ctx, analyser, alive, running, silent);
+        });
+    }
+
+    create_effect(move |_| {
+        let (Some(graph), Some(canvas)) = (graph.get(), canvas.get()) else {
+            return;
+        };
+        graph.played.track();
+        if running.replace(true) {
+            return;
+        }
+
+        let ctx = canvas
+            .get_context("2d")
+            .unwrap_throw()
+            .unwrap_throw()
+            .unchecked_into::<CanvasRenderingContext2d>();
+        ctx.set_fill_style(&"#0ea5e9".into());
+
+        animate(
+            ctx,
+            graph.analyser.clone(),
+            Rc::clone(&alive),
+            Rc::clone(&running),
+            0,
+        );
+    });
+
+    view! {
+        <canvas class=class width=WIDTH height=HEIGHT aria-hidden="true" node_ref=canvas></canvas>
+    }
+}
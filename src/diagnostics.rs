@@ -0,0 +1,162 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+};
+
+use gloo_storage::{LocalStorage, SessionStorage, Storage};
+use leptos::window;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::wasm_bindgen, UnwrapThrowExt};
+use web_sys::HtmlAudioElement;
+
+use crate::{audio::audio_mime, capabilities::Capabilities};
+
+/// Storage keys of the settings that go into a report. Anything that may hold personal data, like
+/// saved transcripts or the names of uploaded sounds in the per-sound settings, is left out.
+const SETTINGS: &[&str] = &[
+    "ducky",
+    "playback_rate",
+    "volume",
+    "intensity_curve",
+    "limiter",
+    "reverb",
+    "echo",
+    "max_voices",
+    "pitch_variation",
+    "bridge",
+    "bridge_port",
+    "sound_pack",
+    "loop_gap",
+    "muted",
+    "keybindings",
+    "low_power",
+    "read_aloud",
+    "speech_voice",
+    "speech_pitch",
+    "speech_rate",
+    "latency",
+    "interject",
+    "interject_pause",
+    "meeting_hours",
+    "meeting_start",
+    "meeting_end",
+];
+
+/// Snapshot of the non-sensitive app state, that helps to reproduce bug reports.
+#[derive(Serialize)]
+pub struct Diagnostics {
+    version: &'static str,
+    user_agent: String,
+    settings: BTreeMap<&'static str, serde_json::Value>,
+    capabilities: Capabilities,
+    /// Result of `canPlayType` for each supported audio file extension.
+    audio_formats: BTreeMap<&'static str, String>,
+    errors: Vec<ErrorReport>,
+}
+
+impl Diagnostics {
+    pub fn collect() -> Self {
+        let audio = HtmlAudioElement::new().unwrap_throw();
+
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            user_agent: window().navigator().user_agent().unwrap_or_default(),
+            settings: SETTINGS
+                .iter()
+                .filter_map(|&key| {
+                    LocalStorage::get::<serde_json::Value>(key)
+                        .ok()
+                        .map(|value| (key, value))
+                })
+                .collect(),
+            capabilities: Capabilities::get(),
+            audio_formats: ["mp3", "webm", "ogg"]
+                .into_iter()
+                .filter_map(|extension| {
+                    audio_mime(extension).map(|mime| (extension, audio.can_play_type(mime)))
+                })
+                .collect(),
+            errors: recent_errors(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// `navigator.clipboard.writeText`, which is still marked unstable in `web-sys`.
+    #[wasm_bindgen(js_namespace = ["navigator", "clipboard"], js_name = writeText)]
+    pub fn write_clipboard(text: &str) -> js_sys::Promise;
+}
+
+thread_local! {
+    /// Most recent errors, for the diagnostics report.
+    static ERRORS: RefCell<VecDeque<ErrorReport>> = RefCell::default();
+}
+
+/// Single entry of the error buffer.
+#[derive(Clone, Serialize)]
+pub struct ErrorReport {
+    /// ISO 8601 timestamp of when the error happened.
+    pub time: String,
+    /// Name of the module that reported the error, like `sounds` or `storage`.
+    pub source: &'static str,
+    pub message: String,
+}
+
+/// Log an error and remember it for the diagnostics report, keeping only the most recent ones.
+pub fn report_error(source: &'static str, message: String) {
+    const CAPACITY: usize = 20;
+
+    warn!("[{source}] {message}");
+    ERRORS.with_borrow_mut(|errors| {
+        if errors.len() == CAPACITY {
+            errors.pop_front();
+        }
+        errors.push_back(ErrorReport {
+            time: js_sys::Date::new_0().to_iso_string().into(),
+            source,
+            message,
+        });
+    });
+}
+
+/// Session storage key of the last panic. The app is gone after a panic, so it is only picked up
+/// again after the page is reloaded.
+const LAST_PANIC: &str = "last_panic";
+
+/// Panic that was kept for the next page load.
+#[derive(Serialize, Deserialize)]
+struct PanicReport {
+    time: String,
+    message: String,
+}
+
+/// Log panics to the console like before, and keep the last one for the error buffer, so it still
+/// shows up after reloading the crashed app. Also restores the panic of a previous load.
+pub fn capture_panics() {
+    if let Ok(PanicReport { time, message }) = SessionStorage::get(LAST_PANIC) {
+        SessionStorage::delete(LAST_PANIC);
+        ERRORS.with_borrow_mut(|errors| {
+            errors.push_back(ErrorReport {
+                time,
+                source: "panic",
+                message,
+            });
+        });
+    }
+
+    std::panic::set_hook(Box::new(|info| {
+        console_error_panic_hook::hook(info);
+        let report = PanicReport {
+            time: js_sys::Date::new_0().to_iso_string().into(),
+            message: info.to_string(),
+        };
+        SessionStorage::set(LAST_PANIC, report).ok();
+    }));
+}
+
+/// Most recent errors, oldest first.
+pub fn recent_errors() -> Vec<ErrorReport> {
+    ERRORS.with_borrow(|errors| errors.iter().cloned().collect())
+}
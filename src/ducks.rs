@@ -0,0 +1,295 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashSet,
+    hash::{Hash, Hasher},
+    ptr,
+    rc::Rc,
+};
+
+use leptos::window;
+use log::debug;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use wasm_bindgen::{closure::Closure, JsCast, UnwrapThrowExt};
+use web_sys::HtmlImageElement;
+
+use crate::audio::{all_sounds, builtin_sounds, enabled_sounds, DisabledSounds, Sound};
+
+/// Duck to pick from, as listed in the [asset manifest](crate::assets).
+#[derive(Clone, Copy, Debug)]
+pub struct Duck(&'static DuckEntry);
+
+#[derive(Debug, Deserialize)]
+pub struct DuckEntry {
+    id: String,
+    name: String,
+    /// Description of the duck's image, for anyone who can't see it.
+    description: String,
+    /// Name of a distinct sound that is played when picking the duck, so it can be told apart by
+    /// ear.
+    preview: String,
+    /// Names of the built-in sounds that the duck quacks with, or empty for all of them.
+    #[serde(default)]
+    voice: Vec<String>,
+    srcset: String,
+}
+
+thread_local! {
+    static DUCKS: Cell<&'static [DuckEntry]> = const { Cell::new(&[]) };
+}
+
+/// Replace the available ducks, which must not be empty. Like runtime sounds, they are leaked.
+pub fn set_ducks(ducks: Vec<DuckEntry>) {
+    DUCKS.set(Box::leak(ducks.into_boxed_slice()));
+}
+
+impl Duck {
+    pub fn iter() -> impl IntoIterator<Item = Self> {
+        DUCKS.get().iter().map(Self)
+    }
+
+    /// Duck with the given `id`, ignoring case.
+    pub fn find(id: &str) -> Option<Self> {
+        Self::iter()
+            .into_iter()
+            .find(|duck| duck.id().eq_ignore_ascii_case(id))
+    }
+
+    pub fn id(self) -> &'static str {
+        &self.0.id
+    }
+
+    pub fn name(self) -> &'static str {
+        &self.0.name
+    }
+
+    /// Description of the duck's image, for anyone who can't see it.
+    pub fn description(self) -> &'static str {
+        &self.0.description
+    }
+
+    /// Distinct sound that is played when picking the duck, so it can be told apart by ear. Falls
+    /// back to the first built-in sound, if the manifest names one that doesn't exist.
+    pub fn preview(self) -> Sound {
+        let sounds = builtin_sounds();
+        sounds
+            .iter()
+            .find(|sound| sound.name == self.0.preview)
+            .unwrap_or(&sounds[0])
+            .to_owned()
+    }
+
+    /// Sounds of the `pack` that are in the duck's voice. Ducks without a voice use the whole pack,
+    /// and so does every duck with a pack that has none of its sounds.
+    pub fn voiced(self, pack: &[Sound]) -> Vec<Sound> {
+        let voiced = pack
+            .iter()
+            .copied()
+            .filter(|sound| self.0.voice.iter().any(|name| name == sound.name))
+            .collect::<Vec<_>>();
+
+        if voiced.is_empty() {
+            pack.to_vec()
+        } else {
+            voiced
+        }
+    }
+
+    /// Sounds that the duck picks from at random: the enabled sounds of the `pack` in its voice,
+    /// together with every enabled custom sound. Disabled sounds are left out first, so a voice
+    /// without any enabled sound falls back to the other enabled sounds instead.
+    pub fn playable(
+        self,
+        pack: &[Sound],
+        custom: &[Sound],
+        disabled: &DisabledSounds,
+    ) -> Vec<Sound> {
+        let (custom, pack) = enabled_sounds(&all_sounds(pack, custom), disabled)
+            .into_iter()
+            .partition::<Vec<_>, _>(|sound| custom.contains(sound));
+        all_sounds(&self.voiced(&pack), &custom)
+    }
+
+    pub fn next(self) -> Self {
+        self.offset(1)
+    }
+
+    pub fn previous(self) -> Self {
+        self.offset(-1)
+    }
+
+    /// Duck that is `by` places away in the list, wrapping around at either end.
+    fn offset(self, by: isize) -> Self {
+        let ducks = DUCKS.get();
+        let index = ducks
+            .iter()
+            .position(|duck| ptr::eq(duck, self.0))
+            .unwrap_or_default();
+        Self(&ducks[(index as isize + by).rem_euclid(ducks.len() as isize) as usize])
+    }
+
+    pub fn srcset(self) -> &'static str {
+        &self.0.srcset
+    }
+
+    /// Image in the lowest density.
+    pub fn src(self) -> &'static str {
+        self.srcset().split(',').next().unwrap_throw()
+    }
+
+    /// Image candidates to load, limited to the lowest density on slow or metered connections,
+    /// where the sharper images aren't worth the wait.
+    pub fn preferred_srcset(self) -> &'static str {
+        if saves_data() {
+            self.src()
+        } else {
+            self.srcset()
+        }
+    }
+}
+
+impl Default for Duck {
+    fn default() -> Self {
+        Self(&DUCKS.get()[0])
+    }
+}
+
+impl PartialEq for Duck {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.id == other.0.id
+    }
+}
+
+impl Eq for Duck {}
+
+impl Hash for Duck {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.id.hash(state);
+    }
+}
+
+/// Stored by ID, so a selection survives changes to the manifest.
+impl Serialize for Duck {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+/// Ignores the case of the ID, as earlier versions stored `One` for the duck with ID `one`. A duck
+/// that was removed from the manifest falls back to the first one.
+impl<'de> Deserialize<'de> for Duck {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Ok(Self::find(&id).unwrap_or_default())
+    }
+}
+
+/// Whether the connection is slow or the user asked to save data, according to the Network
+/// Information API. Always `false` in browsers that don't support it.
+fn saves_data() -> bool {
+    let Ok(connection) = js_sys::Reflect::get(&window().navigator(), &"connection".into()) else {
+        return false;
+    };
+    if connection.is_undefined() {
+        return false;
+    }
+
+    let get = |name: &str| js_sys::Reflect::get(&connection, &name.into()).unwrap_or_default();
+    get("saveData").as_bool().unwrap_or_default()
+        || matches!(
+            get("effectiveType").as_string().as_deref(),
+            Some("slow-2g" | "2g" | "3g")
+        )
+}
+
+/// Start loading the given image `srcset` in the background, so showing it later doesn't flash a
+/// lower resolution first. The browser picks the same density candidate that it would for the
+/// visible image, and every `srcset` is only requested once.
+pub fn prefetch_image(srcset: &'static str) {
+    thread_local! {
+        static PREFETCHED: RefCell<HashSet<&'static str>> = RefCell::default();
+    }
+
+    if PREFETCHED.with_borrow_mut(|prefetched| prefetched.insert(srcset)) {
+        debug!("prefetching {srcset}");
+        HtmlImageElement::new().unwrap_throw().set_srcset(srcset);
+    }
+}
+
+/// Prefetch the images of all ducks one by one while the browser is idle, so switching ducks later
+/// doesn't wait for the network. Holds back as long as `busy` returns `true`, for example while a
+/// sound is playing.
+pub fn prefetch_images_when_idle(busy: impl Fn() -> bool + 'static) {
+    fn next(mut pending: Vec<Duck>, busy: Rc<dyn Fn() -> bool>) {
+        when_idle(move || {
+            if !busy() {
+                if let Some(duck) = pending.pop() {
+                    prefetch_image(duck.preferred_srcset());
+                }
+            }
+            if !pending.is_empty() {
+                next(pending, busy);
+            }
+        });
+    }
+
+    next(Duck::iter().into_iter().collect(), Rc::new(busy));
+}
+
+/// Run the `task` once the browser is idle, or after a short delay where `requestIdleCallback` is
+/// not supported.
+fn when_idle(task: impl FnOnce() + 'static) {
+    let task = Closure::once_into_js(task).unchecked_into();
+    if js_sys::Reflect::has(&window(), &"requestIdleCallback".into()).unwrap_or_default() {
+        window().request_idle_callback(&task).unwrap_throw();
+    } else {
+        window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&task, 200)
+            .unwrap_throw();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sound(name: &str) -> Sound {
+        Sound::leaked(serde_json::from_value(serde_json::json!({ "name": name })).unwrap())
+    }
+
+    fn duck(voice: &[&str]) -> Duck {
+        Duck(Box::leak(Box::new(DuckEntry {
+            id: "test".to_owned(),
+            name: "Test".to_owned(),
+            description: String::new(),
+            preview: String::new(),
+            voice: voice.iter().map(|&name| name.to_owned()).collect(),
+            srcset: String::new(),
+        })))
+    }
+
+    #[test]
+    fn playable_narrows_down_to_voice() {
+        let [a, b, c] = ["a", "b", "c"].map(sound);
+        let custom = sound("custom");
+
+        let playable = duck(&["a"]).playable(&[a, b, c], &[custom], &DisabledSounds::new());
+        assert_eq!(playable, [a, custom]);
+    }
+
+    #[test]
+    fn playable_falls_back_to_enabled_sounds() {
+        let [a, b, c] = ["a", "b", "c"].map(sound);
+        let disabled = DisabledSounds::from(["a".to_owned(), "c".to_owned()]);
+
+        assert_eq!(duck(&["a"]).playable(&[a, b, c], &[], &disabled), [b]);
+    }
+
+    #[test]
+    fn playable_without_voice() {
+        let [a, b] = ["a", "b"].map(sound);
+        assert_eq!(
+            duck(&[]).playable(&[a, b], &[], &DisabledSounds::new()),
+            [a, b]
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::{cell::RefCell, collections::BTreeMap};
+
+use leptos::{ev, prelude::*, window_event_listener};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent};
+
+/// Something that can be triggered with a key.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum Action {
+    Play,
+    OpenSettings,
+    NextDuck,
+    PreviousDuck,
+    Mute,
+}
+
+impl Action {
+    pub fn iter() -> impl IntoIterator<Item = Self> {
+        [
+            Self::Play,
+            Self::OpenSettings,
+            Self::NextDuck,
+            Self::PreviousDuck,
+            Self::Mute,
+        ]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Play => "Play sound",
+            Self::OpenSettings => "Open settings",
+            Self::NextDuck => "Next duck",
+            Self::PreviousDuck => "Previous duck",
+            Self::Mute => "Mute",
+        }
+    }
+
+    fn default_key(self) -> &'static str {
+        match self {
+            Self::Play => " ",
+            Self::OpenSettings => ",",
+            Self::NextDuck => "ArrowRight",
+            Self::PreviousDuck => "ArrowLeft",
+            Self::Mute => "m",
+        }
+    }
+}
+
+/// Assigned key of every action, as reported by [`KeyboardEvent::key`]. Actions without an entry
+/// have no key.
+pub type Keybindings = BTreeMap<Action, String>;
+
+pub fn default_keybindings() -> Keybindings {
+    Action::iter()
+        .into_iter()
+        .map(|action| (action, action.default_key().to_owned()))
+        .collect()
+}
+
+/// Human readable name of a key.
+pub fn key_label(key: &str) -> String {
+    match key {
+        " " => "Space".to_owned(),
+        key if key.chars().count() == 1 => key.to_uppercase(),
+        key => key.to_owned(),
+    }
+}
+
+type Capture = Box<dyn FnOnce(Option<String>)>;
+
+thread_local! {
+    /// Receiver of the next key press, instead of the regular bindings.
+    static CAPTURE: RefCell<Option<Capture>> = RefCell::default();
+}
+
+/// Hand the next key press to `on_key`, instead of triggering its action. Escape cancels the
+/// capture with `None`.
+pub fn capture_next_key(on_key: impl FnOnce(Option<String>) + 'static) {
+    CAPTURE.set(Some(Box::new(on_key)));
+}
+
+/// Install the single window-level key handler, that calls `on_action` whenever the key of one of
+/// the `bindings` is pressed.
+pub fn create_keybindings(bindings: Signal<Keybindings>, on_action: impl Fn(Action) + 'static) {
+    window_event_listener(ev::keydown, move |event: KeyboardEvent| {
+        if event.repeat() || event.ctrl_key() || event.alt_key() || event.meta_key() {
+            return;
+        }
+
+        let key = normalize(&event.key());
+        if let Some(capture) = CAPTURE.take() {
+            event.prevent_default();
+            capture((key != "Escape").then_some(key));
+            return;
+        }
+
+        if is_typing(&event) || (matches!(key.as_str(), " " | "Enter") && is_control(&event)) {
+            return;
+        }
+
+        let action = bindings.with_untracked(|bindings| {
+            bindings
+                .iter()
+                .find_map(|(action, bound)| (*bound == key).then_some(*action))
+        });
+        if let Some(action) = action {
+            event.prevent_default();
+            on_action(action);
+        }
+    });
+}
+
+/// Letters are matched regardless of case, so Shift or Caps Lock don't get in the way.
+fn normalize(key: &str) -> String {
+    if key.chars().count() == 1 {
+        key.to_lowercase()
+    } else {
+        key.to_owned()
+    }
+}
+
+/// Whether the key event goes to a text field, where keys are meant for typing.
+pub fn is_typing(event: &KeyboardEvent) -> bool {
+    target(event).is_some_and(|element| {
+        matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+            || element.is_content_editable()
+    })
+}
+
+/// Whether the key event goes to an element that already reacts to Space or Enter by itself, like
+/// a focused button.
+fn is_control(event: &KeyboardEvent) -> bool {
+    is_typing(event)
+        || target(event).is_some_and(|element| {
+            matches!(element.tag_name().as_str(), "BUTTON" | "A" | "SUMMARY")
+        })
+}
+
+fn target(event: &KeyboardEvent) -> Option<HtmlElement> {
+    event.target()?.dyn_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_ignores_case_of_letters() {
+        assert_eq!(normalize("Q"), "q");
+        assert_eq!(normalize("q"), "q");
+        assert_eq!(normalize("Ä"), "ä");
+    }
+
+    #[test]
+    fn normalize_keeps_named_keys() {
+        assert_eq!(normalize("ArrowLeft"), "ArrowLeft");
+        assert_eq!(normalize(" "), " ");
+    }
+
+    #[test]
+    fn key_label_is_readable() {
+        assert_eq!(key_label(" "), "Space");
+        assert_eq!(key_label("q"), "Q");
+        assert_eq!(key_label("ArrowRight"), "ArrowRight");
+    }
+}
@@ -1,318 +1,295 @@
-use std::fmt::Debug;
-
-use gloo_storage::{errors::StorageError, LocalStorage, Storage};
-use leptos::{
-    component, create_effect, event_target_value, prelude::*, spawn_local, view, Children, For,
-    IntoView,
+mod assets;
+mod audio;
+mod branding;
+mod bridge;
+mod capabilities;
+mod commands;
+mod components;
+mod diagnostics;
+mod ducks;
+mod keys;
+mod media_session;
+mod meeting;
+mod microphone;
+mod packs;
+mod power;
+mod query;
+mod speech;
+mod storage;
+mod time;
+mod transcription;
+mod uploads;
+
+use leptos::{component, document, prelude::*, spawn_local, view, IntoView};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{HtmlElement, MouseEvent};
+
+use crate::{
+    assets::load_assets,
+    audio::{DisabledSounds, FavoriteSounds, Sound, SoundWeights},
+    branding::{APP_NAME, DEFAULT_DUCK, LOGO},
+    bridge::create_bridge,
+    capabilities::Capabilities,
+    commands::register_command,
+    components::{
+        settings::{
+            DEFAULT_BOOST, DEFAULT_BRIDGE, DEFAULT_BRIDGE_PORT, DEFAULT_ECHO,
+            DEFAULT_INTENSITY_CURVE, DEFAULT_INTERJECT, DEFAULT_INTERJECT_PAUSE, DEFAULT_LATENCY,
+            DEFAULT_LIMITER, DEFAULT_LOOP_GAP, DEFAULT_LOW_POWER, DEFAULT_MAX_VOICES,
+            DEFAULT_MEETING_END, DEFAULT_MEETING_HOURS, DEFAULT_MEETING_START, DEFAULT_MICROPHONE,
+            DEFAULT_MUTED, DEFAULT_PITCH_VARIATION, DEFAULT_PLAYBACK_RATE, DEFAULT_READ_ALOUD,
+            DEFAULT_REVERB, DEFAULT_SPEECH_PITCH, DEFAULT_SPEECH_RATE, DEFAULT_SPEECH_VOICE,
+            DEFAULT_VOLUME,
+        },
+        Bench, CommandPalette, Content, DropZone, Footer, Interjection, Navbar, Settings,
+        SoundLink,
+    },
+    diagnostics::capture_panics,
+    ducks::Duck,
+    keys::{create_keybindings, default_keybindings, key_label, Action},
+    meeting::create_in_meeting,
+    microphone::create_microphone_level,
+    packs::{create_sound_packs, selected_sounds, CLASSIC},
+    power::create_battery_low,
+    query::{classroom_mode, query_param, seed_from_query, CLASSROOM_MAX_VOLUME},
+    speech::create_read_aloud,
+    storage::create_stored_signal,
+    uploads::create_custom_sounds,
 };
-use log::{debug, warn};
-use serde::{Deserialize, Serialize};
-use wasm_bindgen::UnwrapThrowExt;
-use wasm_bindgen_futures::JsFuture;
-use web_sys::{Event, HtmlAudioElement};
 
 fn main() {
-    console_error_panic_hook::set_once();
+    capture_panics();
     #[cfg(debug_assertions)]
     console_log::init_with_level(log::Level::Trace).unwrap_throw();
 
-    leptos::mount_to_body(|| view! { <App/> });
+    seed_from_query();
+    document().set_title(&match LOGO {
+        Some(_) => APP_NAME.to_owned(),
+        None => format!("🦆 {APP_NAME}"),
+    });
+    spawn_local(async {
+        load_assets().await;
+        if query_param("diagnostics").is_some() {
+            leptos::mount_to_body(|| view! { <Bench/> });
+        } else {
+            leptos::mount_to_body(|| view! { <App/> });
+        }
+    });
 }
 
-const DEFAULT_PLAYBACK_RATE: f64 = 0.8;
-const DEFAULT_VOLUME: f64 = 0.1;
-
 #[component]
 fn app() -> impl IntoView {
+    let classroom = classroom_mode();
     let show_settings = create_rw_signal(false);
 
-    let ducky = create_stored_signal("ducky", Duck::One);
+    let ducky = create_stored_signal(
+        "ducky",
+        DEFAULT_DUCK.and_then(Duck::find).unwrap_or_default(),
+    );
     let playback_rate = create_stored_signal("playback_rate", DEFAULT_PLAYBACK_RATE);
     let volume = create_stored_signal("volume", DEFAULT_VOLUME);
-
-    view! {
-        <div class="flex flex-col items-center w-screen h-screen">
-            <div class="grow flex flex-col gap-3 items-center place-content-center">
-                <Navbar settings=show_settings/>
-                <Content ducky=ducky playback_rate=playback_rate volume=volume/>
-                <Settings
-                    show=show_settings
-                    playback_rate=playback_rate
-                    volume=volume
-                    selection=ducky
-                />
-            </div>
-            <Footer/>
-        </div>
-    }
-}
-
-#[component]
-fn navbar(#[prop(into)] settings: SignalSetter<bool>) -> impl IntoView {
-    let settings = move |_| settings.set(true);
-
-    view! {
-        <div class="flex gap-2 place-items-center">
-            <div class="text-2xl">"🦆 Quack"</div>
-            <button class="btn p-2" on:click=settings>
-                "Settings"
-            </button>
-        </div>
+    let intensity_curve = create_stored_signal("intensity_curve", DEFAULT_INTENSITY_CURVE.to_vec());
+    let limiter = create_stored_signal("limiter", DEFAULT_LIMITER);
+    let reverb = create_stored_signal("reverb", DEFAULT_REVERB);
+    let echo = create_stored_signal("echo", DEFAULT_ECHO);
+    let max_voices = create_stored_signal("max_voices", DEFAULT_MAX_VOICES);
+    let pitch_variation = create_stored_signal("pitch_variation", DEFAULT_PITCH_VARIATION);
+    let bridge = create_stored_signal("bridge", DEFAULT_BRIDGE);
+    let bridge_port = create_stored_signal("bridge_port", DEFAULT_BRIDGE_PORT);
+    let sound_weights = create_stored_signal("sound_weights", SoundWeights::new());
+    let disabled_sounds = create_stored_signal("disabled_sounds", DisabledSounds::new());
+    let favorite_sounds = create_stored_signal("favorite_sounds", FavoriteSounds::new());
+    let custom_sounds = create_custom_sounds();
+    let sound_packs = create_sound_packs();
+    let sound_pack = create_stored_signal("sound_pack", CLASSIC.to_owned());
+    let pack_sounds = Signal::derive(move || {
+        sound_packs.with(|packs| sound_pack.with(|id| selected_sounds(packs, id)))
+    });
+    let loop_gap = create_stored_signal("loop_gap", DEFAULT_LOOP_GAP);
+    let muted = create_stored_signal("muted", DEFAULT_MUTED);
+    let keybindings = create_stored_signal("keybindings", default_keybindings());
+    let low_power = create_stored_signal("low_power", DEFAULT_LOW_POWER);
+    let read_aloud = create_stored_signal("read_aloud", DEFAULT_READ_ALOUD);
+    let speech_voice = create_stored_signal("speech_voice", DEFAULT_SPEECH_VOICE.to_owned());
+    let speech_pitch = create_stored_signal("speech_pitch", DEFAULT_SPEECH_PITCH);
+    let speech_rate = create_stored_signal("speech_rate", DEFAULT_SPEECH_RATE);
+    let transcripts = create_stored_signal("transcripts", Vec::new());
+    let latency = create_stored_signal("latency", DEFAULT_LATENCY);
+    // Not stored, so the microphone only ever turns on when asked to in this visit.
+    let microphone = create_rw_signal(DEFAULT_MICROPHONE);
+    let interject = create_stored_signal("interject", DEFAULT_INTERJECT);
+    let interject_pause = create_stored_signal("interject_pause", DEFAULT_INTERJECT_PAUSE);
+    let microphone_level = create_microphone_level(microphone);
+    if !classroom {
+        register_command("Turn the microphone mode on or off", move || {
+            microphone.update(|microphone| *microphone = !*microphone);
+        });
     }
-}
-
-#[component]
-fn content(
-    #[prop(into)] ducky: Signal<Duck>,
-    #[prop(into)] playback_rate: Signal<f64>,
-    #[prop(into)] volume: Signal<f64>,
-) -> impl IntoView {
-    view! {
-        <div class="flex flex-col justify-center text-center">
-            <h1 class="text-xl italic">"Rubber Ducking as a service! Finally!"</h1>
-            <img class="my-8 rounded-xl max-w-[400px]" srcset=move || ducky.get().srcset()/>
-
-            <Sounds playback_rate=playback_rate volume=volume/>
-        </div>
+    let battery_low = create_battery_low();
+    let saving_power = Signal::derive(move || low_power.get() || battery_low.get());
+    // Deliberately not stored, so a boost for a loud room doesn't surprise on the next visit.
+    let boost = create_rw_signal(DEFAULT_BOOST);
+    // Not stored either, so a meeting that was never ended doesn't silence the next visit.
+    let meeting = create_rw_signal(false);
+    let meeting_hours = create_stored_signal("meeting_hours", DEFAULT_MEETING_HOURS);
+    let meeting_start = create_stored_signal("meeting_start", DEFAULT_MEETING_START);
+    let meeting_end = create_stored_signal("meeting_end", DEFAULT_MEETING_END);
+    let in_meeting = create_in_meeting(
+        meeting.into(),
+        meeting_hours.into(),
+        meeting_start.into(),
+        meeting_end.into(),
+    );
+    if !classroom {
+        register_command("Start or end a meeting", move || {
+            meeting.update(|meeting| *meeting = !*meeting);
+        });
     }
-}
 
-#[component]
-fn settings(
-    show: RwSignal<bool>,
-    #[prop(into)] playback_rate: RwSignal<f64>,
-    #[prop(into)] volume: RwSignal<f64>,
-    selection: RwSignal<Duck>,
-) -> impl IntoView {
-    let close = move |_| show.set(false);
-
-    let duck_view = move |duck| {
-        let select = move |_| {
-            if selection.get() != duck {
-                selection.set(duck);
-            }
-        };
-
-        view! {
-            <label>
-                <input
-                    class="hidden peer"
-                    type="radio"
-                    name="duck"
-                    checked=move || selection.get() == duck
-                    on:click=select
-                />
-                <img class="settings-duck-image" srcset=duck.srcset()/>
-            </label>
-        }
+    // Requests to play a sound, from anywhere else than the play button itself. `None` picks a
+    // random sound.
+    let quack = create_rw_signal(None::<Sound>);
+    create_bridge(
+        Signal::derive(move || {
+            !classroom && bridge.get() && !saving_power.get() && !in_meeting.get()
+        }),
+        bridge_port.into(),
+        move || quack.set(None),
+    );
+
+    let run_action = move |action| match action {
+        Action::Play => quack.set(None),
+        Action::OpenSettings => show_settings.set(!classroom),
+        Action::NextDuck => ducky.update(|duck| *duck = duck.next()),
+        Action::PreviousDuck => ducky.update(|duck| *duck = duck.previous()),
+        Action::Mute => muted.update(|muted| *muted = !*muted),
     };
-
-    view! {
-        <Dialog show=show>
-            <p class="settings-header">"Pick your duck!"</p>
-            <Slider
-                label="Playback rate"
-                value=playback_rate
-                default=DEFAULT_PLAYBACK_RATE
-                min=0.15
-                max=2.0
-            />
-            <Slider label="Volume" value=volume default=DEFAULT_VOLUME min=0.01 max=1.0/>
-            <div class="settings-ducks">
-                <For each=Duck::iter key=|duck| *duck children=duck_view/>
-            </div>
-            <button class="btn p-2" on:click=close>
-                "Close"
-            </button>
-        </Dialog>
-    }
-}
-
-#[component]
-fn licenses(show: RwSignal<bool>) -> impl IntoView {
-    const PEXELS: &str = "https://www.pexels.com/search/rubber%20duck/";
-    const VIDEVO: &str = "https://www.videvo.net/search/?q=animal+duck+cartoon&mode=sound-effects";
-
-    view! {
-        <Dialog show=show>
-            <p class="settings-header">"Asset Licenses"</p>
-            <div class="text-start">
-                <p class="pb-3">
-                    "Images from "
-                    <a class="link" href=PEXELS target="_blank">"Pexels"</a>
-                </p>
-                <p class="pb-3">
-                    "Most sounds from "
-                    <a class="link" href=VIDEVO target="_blank">"Videvo"</a>
-                </p>
-                <p class="pb-3">
-                    "Individual sounds by: "
-                    <ul class="list-disc list-inside">
-                        <li>
-                            <a class="link" href="https://albertlarsan.fr/" target="_blank">
-                                "Albert Larsan"
-                            </a>
-                        </li>
-                    </ul>
-                </p>
-            </div>
-            <button class="btn p-2" on:click=move |_| show.set(false)>
-                "Close"
-            </button>
-        </Dialog>
-    }
-}
-
-#[component]
-fn dialog(children: Children, #[prop(into)] show: Signal<bool>) -> impl IntoView {
-    view! {
-        <div class="dialog" hidden=move || !show.get()>
-            <div class="dialog-backdrop"></div>
-            <div class="dialog-content">
-                <div class="settings-dialog">{children()}</div>
-            </div>
-        </div>
+    create_keybindings(keybindings.into(), run_action);
+    for action in Action::iter() {
+        register_command(action.label(), move || run_action(action));
     }
-}
-
-const SOUNDS: &[&str] = &[
-    "audio/duck1.mp3",
-    "audio/duck2.mp3",
-    "audio/duck3.mp3",
-    "audio/duck4.mp3",
-    "audio/duck5.mp3",
-    "audio/duck6.mp3",
-    "audio/duck7.mp3",
-    "audio/duck8.mp3",
-    "audio/quack1.mp3",
-];
-
-#[component]
-fn sounds(playback_rate: Signal<f64>, volume: Signal<f64>) -> impl IntoView {
-    let audio = HtmlAudioElement::new().unwrap_throw();
+    create_read_aloud(read_aloud.into());
+    let play_key = Signal::derive(move || {
+        keybindings.with(|bindings| bindings.get(&Action::Play).map(|key| key_label(key)))
+    });
 
-    create_effect({
-        let audio = audio.clone();
-        move |_| {
-            audio.set_default_playback_rate(playback_rate.get());
-            audio.set_volume(volume.get());
+    let output_volume = Signal::derive(move || {
+        if muted.get() {
+            0.0
+        } else if classroom {
+            volume.get().min(CLASSROOM_MAX_VOLUME)
+        } else {
+            volume.get()
         }
     });
 
-    let play = move |_| {
-        let audio = audio.clone();
-        spawn_local(async move {
-            let sound = fastrand::choice(SOUNDS).unwrap();
-            audio.set_src(sound);
-            JsFuture::from(audio.play().unwrap_throw())
-                .await
+    // Focus the button directly instead of following the fragment, so the URL hash stays reserved
+    // for the settings dialog and sound links.
+    let skip_to_play = |event: MouseEvent| {
+        event.prevent_default();
+        if let Some(button) = document().get_element_by_id("play") {
+            button
+                .unchecked_into::<HtmlElement>()
+                .focus()
                 .unwrap_throw();
-        });
-    };
-
-    view! {
-        <button
-            class="p-3 text-3xl bg-green-600 rounded-full border-2 border-green-700 transition-all hover:bg-green-700 hover:border-green-600 max-w-[400px]"
-            on:click=play
-        >
-            "🔊 Play Sound"
-        </button>
-    }
-}
-
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
-enum Duck {
-    One,
-    Two,
-    Three,
-    Four,
-}
-
-impl Duck {
-    fn iter() -> impl IntoIterator<Item = Self> {
-        [Self::One, Self::Two, Self::Three, Self::Four]
-    }
-
-    fn srcset(self) -> &'static str {
-        match self {
-            Self::One => "image/duck1.webp, image/duck1@2x.webp 2x, image/duck1@4x.webp 4x",
-            Self::Two => "image/duck2.webp, image/duck2@2x.webp 2x, image/duck2@4x.webp 4x",
-            Self::Three => "image/duck3.webp, image/duck3@2x.webp 2x, image/duck3@4x.webp 4x",
-            Self::Four => "image/duck4.webp, image/duck4@2x.webp 2x, image/duck4@4x.webp 4x",
         }
-    }
-}
-
-#[component]
-fn slider(
-    label: &'static str,
-    value: RwSignal<f64>,
-    default: f64,
-    min: f64,
-    max: f64,
-) -> impl IntoView {
-    let input = move |event: Event| value.set(event_target_value(&event).parse().unwrap_throw());
-    let reset = move |_| value.set(default);
+    };
 
     view! {
-        <div class="slider">
-            <span class="w-32">{label}</span>
-            <input
-                class="grow"
-                type="range"
-                min=min
-                max=max
-                step="any"
-                value=value
-                prop:value=value
-                on:change=input
+        <div class="flex flex-col items-center w-screen h-screen" class:classroom=classroom>
+            <a class="skip-link" href="#play" on:click=skip_to_play>
+                "Skip to play button"
+            </a>
+            <div class="grow flex flex-col gap-3 items-center place-content-center">
+                <Navbar settings=show_settings muted=muted classroom=classroom/>
+                <Content
+                    ducky=ducky
+                    playback_rate=playback_rate
+                    volume=output_volume
+                    intensity_curve=intensity_curve
+                    limiter=limiter
+                    boost=boost
+                    max_voices=max_voices
+                    pitch_variation=pitch_variation
+                    reverb=reverb
+                    echo=echo
+                    saving_power=saving_power
+                    in_meeting=in_meeting
+                    loop_gap=loop_gap
+                    play_key=play_key
+                    sound_weights=sound_weights
+                    disabled_sounds=disabled_sounds
+                    favorite_sounds=favorite_sounds
+                    pack_sounds=pack_sounds
+                    sound_pack=sound_pack
+                    custom_sounds=custom_sounds
+                    speech_voice=speech_voice
+                    speech_pitch=speech_pitch
+                    speech_rate=speech_rate
+                    transcripts=transcripts
+                    latency=latency
+                    microphone_level=microphone_level
+                    quack=quack
+                    classroom=classroom
+                />
+                {(!classroom)
+                    .then(|| {
+                        view! {
+                            <Settings
+                                show=show_settings
+                                playback_rate=playback_rate
+                                volume=volume
+                                muted=muted
+                                intensity_curve=intensity_curve
+                                limiter=limiter
+                                boost=boost
+                                max_voices=max_voices
+                                pitch_variation=pitch_variation
+                                reverb=reverb
+                                echo=echo
+                                low_power=low_power
+                                read_aloud=read_aloud
+                                meeting=meeting
+                                meeting_hours=meeting_hours
+                                meeting_start=meeting_start
+                                meeting_end=meeting_end
+                                in_meeting=in_meeting
+                                speech_voice=speech_voice
+                                speech_pitch=speech_pitch
+                                speech_rate=speech_rate
+                                latency=latency
+                                microphone=microphone
+                                interject=interject
+                                interject_pause=interject_pause
+                                loop_gap=loop_gap
+                                keybindings=keybindings
+                                sound_weights=sound_weights
+                                disabled_sounds=disabled_sounds
+                                favorite_sounds=favorite_sounds
+                                sound_packs=sound_packs
+                                sound_pack=sound_pack
+                                pack_sounds=pack_sounds
+                                custom_sounds=custom_sounds
+                                bridge=bridge
+                                bridge_port=bridge_port
+                                selection=ducky
+                                quack=quack
+                            />
+                        }
+                    })}
+            </div>
+            {(!classroom).then(|| view! { <Footer/> })}
+            <CommandPalette/>
+            <SoundLink pack_sounds=pack_sounds custom_sounds=custom_sounds quack=quack/>
+            <Interjection
+                level=microphone_level
+                enabled=interject
+                pause=interject_pause
+                in_meeting=in_meeting
+                quack=quack
             />
-            <button class="btn py-0.5 px-1" on:click=reset>
-                "Reset"
-            </button>
+            {(!classroom && Capabilities::get().indexed_db)
+                .then(|| view! { <DropZone custom_sounds=custom_sounds/> })}
         </div>
     }
 }
-
-#[component]
-fn footer() -> impl IntoView {
-    const GITHUB: &str = "https://github.com/dnaka91/quack";
-
-    let show_licenses = create_rw_signal(false);
-
-    view! {
-        <>
-            <div class="footer my-4 flex-initial">
-                <button class="link" on:click=move |_| show_licenses.set(true)>"Licenses"</button>
-                " • "
-                "Source on " <a class="link" href=GITHUB target="_blank">
-                    "GitHub"
-                </a>
-            </div>
-            <Licenses show=show_licenses/>
-        </>
-    }
-}
-
-fn create_stored_signal<T>(key: &'static str, default: T) -> RwSignal<T>
-where
-    T: Clone + Debug + Serialize,
-    for<'de> T: Deserialize<'de>,
-{
-    let signal = create_rw_signal(match LocalStorage::get(key) {
-        Ok(value) => value,
-        Err(StorageError::KeyNotFound(_)) => default,
-        Err(e) => {
-            warn!("failed loading `{key}` from storage:\n{e:?}");
-            default
-        }
-    });
-
-    create_effect(move |_| {
-        let value = signal.get();
-        debug!("changed {key}: {value:.2?}");
-        LocalStorage::set(key, value).unwrap_throw();
-    });
-
-    signal
-}
@@ -0,0 +1,104 @@
+use std::{cell::Cell, rc::Rc};
+
+use js_sys::{Array, Function, Object, Reflect};
+use leptos::window;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+use crate::{audio::Sound, branding::APP_NAME, ducks::Duck};
+
+/// Media session of the page, if the browser supports the Media Session API.
+fn session() -> Option<JsValue> {
+    Reflect::get(&window().navigator(), &"mediaSession".into())
+        .ok()
+        .filter(|session| !session.is_undefined())
+}
+
+/// Show the `sound` as playing in the media controls of the operating system, with the `duck` as
+/// artwork. The metadata is only replaced when either of them changed, as the same sound is often
+/// played many times in a row.
+pub fn show_playing(sound: Sound, duck: Duck) {
+    thread_local! {
+        static SHOWN: Cell<Option<(Sound, Duck)>> = const { Cell::new(None) };
+    }
+
+    let Some(session) = session() else {
+        return;
+    };
+    set_playback_state(&session, "playing");
+    if SHOWN.get() == Some((sound, duck)) {
+        return;
+    }
+    let Some(constructor) = Reflect::get(&window(), &"MediaMetadata".into())
+        .ok()
+        .and_then(|constructor| constructor.dyn_into::<Function>().ok())
+    else {
+        return;
+    };
+
+    let artwork = Object::new();
+    let init = Object::new();
+    let set = |target: &Object, name: &str, value: &JsValue| {
+        Reflect::set(target, &name.into(), value).ok();
+    };
+    set(&artwork, "src", &duck.src().into());
+    set(&init, "title", &APP_NAME.into());
+    set(&init, "artist", &"Rubber Duck".into());
+    set(&init, "album", &sound.name.into());
+    set(&init, "artwork", &Array::of1(&artwork));
+
+    if let Ok(metadata) = Reflect::construct(&constructor, &Array::of1(&init)) {
+        set(session.unchecked_ref(), "metadata", &metadata);
+        SHOWN.set(Some((sound, duck)));
+    }
+}
+
+/// Show in the media controls that nothing is playing anymore.
+pub fn show_stopped() {
+    if let Some(session) = session() {
+        set_playback_state(&session, "paused");
+    }
+}
+
+fn set_playback_state(session: &JsValue, state: &str) {
+    Reflect::set(session, &"playbackState".into(), &state.into()).ok();
+}
+
+/// Let the play key of keyboards and headsets, and the media controls of the operating system,
+/// call `on_play`, and their pause and stop keys `on_stop`. Only works while the page played a
+/// sound recently, as the operating system picks whichever page played last.
+pub fn create_media_session(on_play: impl Fn() + 'static, on_stop: impl Fn() + 'static) {
+    let Some(session) = session() else {
+        return;
+    };
+    let Some(set_action_handler) = Reflect::get(&session, &"setActionHandler".into())
+        .ok()
+        .and_then(|f| f.dyn_into::<Function>().ok())
+    else {
+        return;
+    };
+
+    let on_stop = Rc::new(move || {
+        show_stopped();
+        on_stop();
+    });
+    let handlers = [
+        ("play", Closure::<dyn Fn()>::new(on_play)),
+        (
+            "pause",
+            Closure::new({
+                let on_stop = Rc::clone(&on_stop);
+                move || on_stop()
+            }),
+        ),
+        ("stop", Closure::new(move || on_stop())),
+    ];
+
+    for (action, handler) in handlers {
+        // Browsers throw for actions they don't know, which just leaves that key unused.
+        set_action_handler
+            .call2(&session, &action.into(), handler.as_ref())
+            .ok();
+        // The handlers are kept for the whole lifetime of the app.
+        handler.forget();
+    }
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use js_sys::Date;
+use leptos::{create_memo, prelude::*, set_interval};
+
+/// Whether automatic sounds should hold back, because the user is in a meeting. That is while
+/// `manual` is on, or while `scheduled` within the daily meeting hours from `start` until `end`,
+/// which may wrap around midnight.
+pub fn create_in_meeting(
+    manual: Signal<bool>,
+    scheduled: Signal<bool>,
+    start: Signal<u8>,
+    end: Signal<u8>,
+) -> Signal<bool> {
+    /// How often the hour of the day is checked again.
+    const CHECK: Duration = Duration::from_secs(60);
+
+    let hour = create_rw_signal(current_hour());
+    // Lives as long as the app.
+    set_interval(move || hour.set(current_hour()), CHECK);
+
+    // A memo, so the minutely check doesn't disturb anything that reacts to this, like the bridge.
+    create_memo(move |_| {
+        manual.get() || (scheduled.get() && within(hour.get(), start.get(), end.get()))
+    })
+    .into()
+}
+
+fn current_hour() -> u8 {
+    Date::new_0().get_hours() as u8
+}
+
+fn within(hour: u8, start: u8, end: u8) -> bool {
+    if start <= end {
+        (start..end).contains(&hour)
+    } else {
+        hour >= start || hour < end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn within_same_day() {
+        assert!(!within(8, 9, 17));
+        assert!(within(9, 9, 17));
+        assert!(within(16, 9, 17));
+        assert!(!within(17, 9, 17));
+    }
+
+    #[test]
+    fn within_across_midnight() {
+        assert!(within(22, 22, 6));
+        assert!(within(23, 22, 6));
+        assert!(within(0, 22, 6));
+        assert!(within(5, 22, 6));
+        assert!(!within(6, 22, 6));
+        assert!(!within(12, 22, 6));
+    }
+
+    #[test]
+    fn within_empty_hours() {
+        assert!(!within(9, 9, 9));
+    }
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use leptos::{create_effect, prelude::*, spawn_local, store_value, window};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AudioContext, MediaStream, MediaStreamConstraints, MediaStreamTrack};
+
+use crate::{
+    audio::is_not_allowed,
+    diagnostics::report_error,
+    time::{now, sleep},
+};
+
+/// Ask for access to the microphone.
+pub async fn open_microphone() -> Result<MediaStream, JsValue> {
+    Ok(JsFuture::from(
+        window()
+            .navigator()
+            .media_devices()?
+            .get_user_media_with_constraints(MediaStreamConstraints::new().audio(&true.into()))?,
+    )
+    .await?
+    .unchecked_into())
+}
+
+/// Stop using the microphone, which also turns off the browser's recording indicator.
+pub fn close_microphone(stream: &MediaStream) {
+    for track in stream.get_tracks() {
+        track.unchecked_into::<MediaStreamTrack>().stop();
+    }
+}
+
+/// Loudness of the microphone while `enabled`, as root mean square of the samples from 0 to 1. It
+/// is updated with every new measurement, even if it didn't change, and stays at 0 otherwise.
+/// Turns `enabled` off again if the microphone permission is refused.
+pub fn create_microphone_level(enabled: RwSignal<bool>) -> Signal<f64> {
+    let level = create_rw_signal(0.0);
+    // Bumped whenever the microphone is turned on or off, so a running measurement notices that
+    // it should end.
+    let run = store_value(0_u32);
+
+    create_effect(move |_| {
+        run.update_value(|run| *run += 1);
+        level.set(0.0);
+        if !enabled.get() {
+            return;
+        }
+
+        let this_run = run.get_value();
+        spawn_local(async move {
+            match measure(|| run.get_value() == this_run, level).await {
+                Ok(()) => {}
+                Err(e) if is_not_allowed(&e) => enabled.set(false),
+                Err(e) => {
+                    report_error("microphone", format!("failed listening:\n{e:?}"));
+                    enabled.set(false);
+                }
+            }
+        });
+    });
+
+    level.into()
+}
+
+async fn measure(active: impl Fn() -> bool, level: RwSignal<f64>) -> Result<(), JsValue> {
+    /// Fast enough for an animation to follow the voice.
+    const INTERVAL: Duration = Duration::from_millis(50);
+
+    let stream = open_microphone().await?;
+    let context = AudioContext::new()?;
+    let analyser = context.create_analyser()?;
+    context
+        .create_media_stream_source(&stream)?
+        .connect_with_audio_node(&analyser)?;
+
+    let mut samples = vec![0.0_f32; analyser.fft_size() as usize];
+    while active() {
+        analyser.get_float_time_domain_data(&mut samples);
+        let power =
+            samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32;
+        level.set(f64::from(power.sqrt()));
+        sleep(INTERVAL).await;
+    }
+
+    close_microphone(&stream);
+    JsFuture::from(context.close()?).await?;
+    Ok(())
+}
+
+/// Call `on_pause` once the microphone `level` stays below speaking volume for `pause` seconds,
+/// after the user spoke. It is only called once per pause.
+pub fn create_pause_detector(
+    level: Signal<f64>,
+    pause: Signal<f64>,
+    on_pause: impl Fn() + 'static,
+) {
+    /// Level above which the microphone most likely picks up a voice, rather than the room.
+    const SPEAKING: f64 = 0.02;
+
+    let last_voice = store_value(None::<f64>);
+    create_effect(move |_| {
+        let now = now();
+        if level.get() > SPEAKING {
+            last_voice.set_value(Some(now));
+        } else if last_voice
+            .get_value()
+            .is_some_and(|last| now - last > pause.get_untracked() * 1000.0)
+        {
+            last_voice.set_value(None);
+            on_pause();
+        }
+    });
+}
@@ -0,0 +1,88 @@
+use leptos::{prelude::*, spawn_local};
+use serde::Deserialize;
+
+use crate::{
+    audio::{builtin_sounds, fetch_optional_json, Sound, SoundEntry},
+    diagnostics::report_error,
+};
+
+/// ID of the pack with the built-in sounds.
+pub const CLASSIC: &str = "classic";
+
+/// Named set of sounds, that the random selection draws from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoundPack {
+    pub id: String,
+    pub name: String,
+    pub sounds: Vec<Sound>,
+}
+
+impl SoundPack {
+    fn classic() -> Self {
+        Self {
+            id: CLASSIC.to_owned(),
+            name: "Classic".to_owned(),
+            sounds: builtin_sounds().to_vec(),
+        }
+    }
+}
+
+/// Contents of `audio/packs.json`, which lists extra sound packs.
+#[derive(Deserialize)]
+struct Manifest {
+    packs: Vec<PackEntry>,
+}
+
+#[derive(Deserialize)]
+struct PackEntry {
+    id: String,
+    name: String,
+    sounds: Vec<SoundEntry>,
+}
+
+/// All available sound packs. The built-in pack comes first and is joined by the ones from the
+/// optional manifest, once it is loaded.
+pub fn create_sound_packs() -> RwSignal<Vec<SoundPack>> {
+    let packs = create_rw_signal(vec![SoundPack::classic()]);
+
+    spawn_local(async move {
+        match fetch_optional_json::<Manifest>("audio/packs.json").await {
+            Ok(Some(manifest)) => packs.update(|packs| {
+                packs.extend(
+                    manifest
+                        .packs
+                        .into_iter()
+                        .filter(|pack| {
+                            // A pack without sounds would leave nothing to play.
+                            if pack.sounds.is_empty() {
+                                report_error(
+                                    "packs",
+                                    format!("skipped sound pack `{}` without sounds", pack.id),
+                                );
+                            }
+                            pack.id != CLASSIC && !pack.sounds.is_empty()
+                        })
+                        .map(|pack| SoundPack {
+                            id: pack.id,
+                            name: pack.name,
+                            sounds: pack.sounds.into_iter().map(Sound::leaked).collect(),
+                        }),
+                );
+            }),
+            Ok(None) => {}
+            Err(e) => report_error("packs", format!("failed loading sound packs:\n{e:?}")),
+        }
+    });
+
+    packs
+}
+
+/// Sounds of the pack with the given `id`, or of the built-in pack if it doesn't exist (anymore).
+pub fn selected_sounds(packs: &[SoundPack], id: &str) -> Vec<Sound> {
+    packs
+        .iter()
+        .find(|pack| pack.id == id)
+        .or_else(|| packs.first())
+        .map(|pack| pack.sounds.clone())
+        .unwrap_or_default()
+}
@@ -0,0 +1,54 @@
+use js_sys::{Function, Reflect};
+use leptos::{prelude::*, spawn_local, window};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue, UnwrapThrowExt};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::EventTarget;
+
+/// Whether the battery is discharging and almost empty, according to the Battery Status API.
+/// Always `false` in browsers that don't support it.
+pub fn create_battery_low() -> Signal<bool> {
+    /// Charge level below which the battery counts as low.
+    const THRESHOLD: f64 = 0.2;
+
+    let low = create_rw_signal(false);
+    let navigator = window().navigator();
+    let Some(get_battery) = Reflect::get(&navigator, &"getBattery".into())
+        .ok()
+        .and_then(|f| f.dyn_into::<Function>().ok())
+    else {
+        return low.into();
+    };
+
+    spawn_local(async move {
+        let Ok(promise) = get_battery.call0(&navigator) else {
+            return;
+        };
+        let Ok(battery) = JsFuture::from(js_sys::Promise::from(promise)).await else {
+            return;
+        };
+
+        let update = {
+            let battery = battery.clone();
+            move || {
+                let get =
+                    |name: &str| Reflect::get(&battery, &name.into()).unwrap_or(JsValue::NULL);
+                let charging = get("charging").as_bool().unwrap_or(true);
+                let level = get("level").as_f64().unwrap_or(1.0);
+                low.set(!charging && level <= THRESHOLD);
+            }
+        };
+        update();
+
+        let target = battery.unchecked_into::<EventTarget>();
+        let listener = Closure::<dyn Fn()>::new(update);
+        for event in ["chargingchange", "levelchange"] {
+            target
+                .add_event_listener_with_callback(event, listener.as_ref().unchecked_ref())
+                .unwrap_throw();
+        }
+        // The battery is watched for the whole lifetime of the app.
+        listener.forget();
+    });
+
+    low.into()
+}
@@ -0,0 +1,61 @@
+use gloo_storage::{LocalStorage, Storage};
+use leptos::window;
+use log::debug;
+use wasm_bindgen::UnwrapThrowExt;
+use web_sys::UrlSearchParams;
+
+use crate::{audio::seed_selection, capabilities::Capabilities, diagnostics::report_error};
+
+/// Seed the random selection of sounds from a `?seed=<number>` query parameter, so demos and screen
+/// recordings play the same sequence of sounds on every load.
+pub fn seed_from_query() {
+    let Some(seed) = query_param("seed") else {
+        return;
+    };
+
+    match seed.parse() {
+        Ok(seed) => {
+            debug!("using fixed random seed {seed}");
+            seed_selection(seed);
+        }
+        Err(e) => report_error("query", format!("invalid random seed `{seed}`: {e}")),
+    }
+}
+
+/// Simplified mode for classrooms, enabled with a `?classroom` query parameter. Opening the app
+/// with `?classroom=lock` keeps the mode on for later visits without the parameter, until it is
+/// opened with `?classroom=unlock` again.
+pub fn classroom_mode() -> bool {
+    const LOCK: &str = "classroom_lock";
+
+    // Without usable storage, the lock can't outlast the visit, but the mode itself still works.
+    let storage = Capabilities::get().storage;
+
+    match query_param("classroom").as_deref() {
+        Some("lock") => {
+            if storage {
+                if let Err(e) = LocalStorage::set(LOCK, true) {
+                    report_error("query", format!("failed locking classroom mode:\n{e:?}"));
+                }
+            }
+            true
+        }
+        Some("unlock") => {
+            if storage {
+                LocalStorage::delete(LOCK);
+            }
+            false
+        }
+        Some(_) => true,
+        None => storage && LocalStorage::get(LOCK).unwrap_or_default(),
+    }
+}
+
+/// Loudest volume allowed in classroom mode, regardless of the stored setting.
+pub const CLASSROOM_MAX_VOLUME: f64 = 0.3;
+
+pub fn query_param(name: &str) -> Option<String> {
+    UrlSearchParams::new_with_str(&window().location().search().unwrap_throw())
+        .unwrap_throw()
+        .get(name)
+}
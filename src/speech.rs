@@ -0,0 +1,100 @@
+use leptos::{ev, prelude::*, window, window_event_listener};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{Element, FocusEvent, SpeechSynthesisUtterance, SpeechSynthesisVoice};
+
+/// Speak `text` with the browser's speech synthesis, cutting off whatever was spoken before. Does
+/// nothing in browsers without speech synthesis.
+pub fn announce(text: &str) {
+    say(text, |_| {});
+}
+
+/// Like [`announce`], but with the given `voice`, `pitch` and `rate`. An unknown `voice`, like an
+/// empty one, leaves the choice to the browser.
+pub fn speak(text: &str, voice: &str, pitch: f64, rate: f64) {
+    let voice = voices()
+        .into_iter()
+        .find(|candidate| candidate.name() == voice);
+    say(text, |utterance| {
+        utterance.set_voice(voice.as_ref());
+        utterance.set_pitch(pitch as f32);
+        utterance.set_rate(rate as f32);
+    });
+}
+
+fn say(text: &str, configure: impl FnOnce(&SpeechSynthesisUtterance)) {
+    let Ok(synthesis) = window().speech_synthesis() else {
+        return;
+    };
+    let Ok(utterance) = SpeechSynthesisUtterance::new_with_text(text) else {
+        return;
+    };
+    configure(&utterance);
+
+    synthesis.cancel();
+    synthesis.speak(&utterance);
+}
+
+fn voices() -> Vec<SpeechSynthesisVoice> {
+    window().speech_synthesis().map_or_else(
+        |_| Vec::new(),
+        |synthesis| {
+            synthesis
+                .get_voices()
+                .into_iter()
+                .map(JsCast::unchecked_into)
+                .collect()
+        },
+    )
+}
+
+/// Names of the voices that [`speak`] can use. Browsers load them in the background, so the list
+/// may only fill up after a moment.
+pub fn create_voices() -> Signal<Vec<String>> {
+    let names = move || voices().iter().map(SpeechSynthesisVoice::name).collect();
+    let voices = create_rw_signal(names());
+
+    if let Ok(synthesis) = window().speech_synthesis() {
+        let changed = Closure::<dyn Fn()>::new(move || voices.set(names()));
+        synthesis.set_onvoiceschanged(Some(changed.as_ref().unchecked_ref()));
+        // The voices are watched for the whole lifetime of the app.
+        changed.forget();
+    }
+
+    voices.into()
+}
+
+/// Read out every element that receives focus while `enabled`, for users whose screen reader
+/// doesn't do a good job in their browser.
+pub fn create_read_aloud(enabled: Signal<bool>) {
+    // Lives as long as the app.
+    window_event_listener(ev::focusin, move |event: FocusEvent| {
+        if !enabled.get_untracked() {
+            return;
+        }
+
+        if let Some(name) = event
+            .target()
+            .and_then(|target| target.dyn_into::<Element>().ok())
+            .and_then(|element| accessible_name(&element))
+        {
+            announce(&name);
+        }
+    });
+}
+
+/// Rough approximation of the name that a screen reader would use for the `element`. Form fields
+/// are usually wrapped in their label in this app, so that one is used for them.
+fn accessible_name(element: &Element) -> Option<String> {
+    let name = element
+        .get_attribute("aria-label")
+        .or_else(|| {
+            matches!(element.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA")
+                .then(|| element.closest("label").ok().flatten()?.text_content())
+                .flatten()
+        })
+        .or_else(|| element.get_attribute("title"))
+        .or_else(|| element.text_content())?;
+
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!name.is_empty()).then_some(name)
+}
@@ -0,0 +1,46 @@
+use std::fmt::Debug;
+
+use gloo_storage::{errors::StorageError, LocalStorage, Storage};
+use leptos::{create_effect, prelude::*};
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{capabilities::Capabilities, diagnostics::report_error};
+
+/// Signal that is kept in local storage. Without usable storage, it only lasts until the page is
+/// closed.
+pub fn create_stored_signal<T>(key: &'static str, default: T) -> RwSignal<T>
+where
+    T: Clone + Debug + Serialize,
+    for<'de> T: Deserialize<'de>,
+{
+    if !Capabilities::get().storage {
+        return create_rw_signal(default);
+    }
+
+    let signal = create_rw_signal(match LocalStorage::get(key) {
+        Ok(value) => value,
+        Err(StorageError::KeyNotFound(_)) => default,
+        Err(e) => {
+            report_error(
+                "storage",
+                format!("failed loading `{key}` from storage:\n{e:?}"),
+            );
+            default
+        }
+    });
+
+    create_effect(move |_| {
+        let value = signal.get();
+        debug!("changed {key}: {value:.2?}");
+        // A full or revoked storage only costs persistence, not the whole app.
+        if let Err(e) = LocalStorage::set(key, value) {
+            report_error(
+                "storage",
+                format!("failed saving `{key}` to storage:\n{e:?}"),
+            );
+        }
+    });
+
+    signal
+}
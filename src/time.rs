@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+use js_sys::Promise;
+use leptos::window;
+use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen_futures::JsFuture;
+
+/// High resolution timestamp in milliseconds, relative to the page load.
+pub fn now() -> f64 {
+    window().performance().unwrap_throw().now()
+}
+
+/// Wait for the given `duration`, without blocking the browser.
+pub async fn sleep(duration: Duration) {
+    JsFuture::from(Promise::new(&mut |resolve, _| {
+        window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                duration.as_millis().try_into().unwrap_throw(),
+            )
+            .unwrap_throw();
+    }))
+    .await
+    .unwrap_throw();
+}
@@ -0,0 +1,117 @@
+use js_sys::{Function, Reflect};
+use leptos::window;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+/// Number of saved transcripts to keep, dropping the oldest ones beyond it.
+pub const MAX_TRANSCRIPTS: usize = 20;
+
+/// Transcript that was saved to the history, to look back at later.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedTranscript {
+    /// Time of saving, as ISO 8601 string.
+    pub saved: String,
+    pub text: String,
+}
+
+/// Why a recognition ended early.
+pub enum RecognitionError {
+    /// The microphone permission was refused.
+    NotAllowed,
+    Other(String),
+}
+
+/// Running speech recognition, together with its callbacks that must live as long as it does.
+/// Stops when dropped.
+pub struct Recognition {
+    recognition: JsValue,
+    _on_result: Closure<dyn FnMut(JsValue)>,
+    _on_error: Closure<dyn FnMut(JsValue)>,
+    _on_end: Closure<dyn FnMut()>,
+}
+
+impl Drop for Recognition {
+    fn drop(&mut self) {
+        for handler in ["onresult", "onerror", "onend"] {
+            Reflect::set(&self.recognition, &handler.into(), &JsValue::NULL).ok();
+        }
+        call(&self.recognition, "abort").ok();
+    }
+}
+
+/// Listen to the microphone with the Web Speech API, calling `on_result` with the whole transcript
+/// so far whenever it changes, including words that may still be corrected. Chromium based
+/// browsers only have it under a prefix, which is used as fallback.
+pub fn start_recognition(
+    on_result: impl Fn(String) + 'static,
+    on_error: impl Fn(RecognitionError) + 'static,
+    on_end: impl Fn() + 'static,
+) -> Result<Recognition, JsValue> {
+    let window = window();
+    let constructor = ["SpeechRecognition", "webkitSpeechRecognition"]
+        .into_iter()
+        .find_map(|name| {
+            Reflect::get(&window, &name.into())
+                .ok()
+                .and_then(|constructor| constructor.dyn_into::<Function>().ok())
+        })
+        .ok_or("speech recognition is not supported")?;
+    let recognition = Reflect::construct(&constructor, &js_sys::Array::new())?;
+
+    let set = |name: &str, value: &JsValue| Reflect::set(&recognition, &name.into(), value);
+    set("continuous", &true.into())?;
+    set("interimResults", &true.into())?;
+
+    let on_result = Closure::<dyn FnMut(_)>::new(move |event: JsValue| {
+        on_result(transcript(&event).unwrap_or_default());
+    });
+    let on_error = Closure::<dyn FnMut(_)>::new(move |event: JsValue| {
+        let error = Reflect::get(&event, &"error".into())
+            .ok()
+            .and_then(|error| error.as_string())
+            .unwrap_or_default();
+        on_error(match error.as_str() {
+            "not-allowed" | "service-not-allowed" => RecognitionError::NotAllowed,
+            // Silence is nothing to complain about.
+            "no-speech" | "aborted" => return,
+            _ => RecognitionError::Other(error),
+        });
+    });
+    let on_end = Closure::<dyn FnMut()>::new(on_end);
+    set("onresult", on_result.as_ref())?;
+    set("onerror", on_error.as_ref())?;
+    set("onend", on_end.as_ref())?;
+
+    call(&recognition, "start")?;
+
+    Ok(Recognition {
+        recognition,
+        _on_result: on_result,
+        _on_error: on_error,
+        _on_end: on_end,
+    })
+}
+
+/// Join the best alternative of every result in a `result` event.
+fn transcript(event: &JsValue) -> Result<String, JsValue> {
+    let results = Reflect::get(event, &"results".into())?;
+    let length = Reflect::get(&results, &"length".into())?
+        .as_f64()
+        .unwrap_or_default() as u32;
+
+    let mut text = String::new();
+    for index in 0..length {
+        let best = Reflect::get_u32(&Reflect::get_u32(&results, index)?, 0)?;
+        if let Some(part) = Reflect::get(&best, &"transcript".into())?.as_string() {
+            text.push_str(&part);
+        }
+    }
+
+    Ok(text.trim().to_owned())
+}
+
+fn call(target: &JsValue, method: &str) -> Result<JsValue, JsValue> {
+    Reflect::get(target, &method.into())?
+        .unchecked_into::<Function>()
+        .call0(target)
+}
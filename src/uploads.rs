@@ -0,0 +1,195 @@
+use std::iter;
+
+use js_sys::{Array, Promise};
+use leptos::{prelude::*, spawn_local, window};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue, UnwrapThrowExt};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Blob, File, FileList, IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode, Url,
+};
+
+use crate::{audio::Sound, diagnostics::report_error};
+
+const DATABASE: &str = "quack";
+/// Object store of the custom sounds, with the sound name as key and the audio file as value.
+const STORE: &str = "sounds";
+/// Largest file that is accepted as custom sound, in bytes. Quacks are short, so anything bigger is
+/// most likely the wrong file.
+const MAX_SIZE: f64 = 2.0 * 1024.0 * 1024.0;
+/// File extensions of accepted sounds, for files without a MIME type.
+const EXTENSIONS: &[&str] = &["mp3", "ogg", "opus", "wav", "webm"];
+
+/// Sounds that the user added, which are loaded from IndexedDB in the background.
+pub fn create_custom_sounds() -> RwSignal<Vec<Sound>> {
+    let sounds = create_rw_signal(Vec::new());
+
+    spawn_local(async move {
+        match load().await {
+            Ok(loaded) => sounds.update(|sounds| sounds.extend(loaded)),
+            Err(e) => report_error("uploads", format!("failed loading custom sounds:\n{e:?}")),
+        }
+    });
+
+    sounds
+}
+
+/// Files of a file input or drop.
+pub fn file_list(files: &FileList) -> Vec<File> {
+    (0..files.length()).filter_map(|i| files.item(i)).collect()
+}
+
+/// Store the audio `files` as custom sounds, named after the files. Returns why some of the files
+/// were rejected right away, without trying to store them.
+pub fn import(files: Vec<File>, sounds: RwSignal<Vec<Sound>>) -> Vec<String> {
+    let mut rejected = Vec::new();
+    let files = files
+        .into_iter()
+        .filter(|file| validate(file).map_err(|e| rejected.push(e)).is_ok())
+        .collect::<Vec<_>>();
+
+    spawn_local(async move {
+        for file in files {
+            let stem = file
+                .name()
+                .rsplit_once('.')
+                .map_or_else(|| file.name(), |(stem, _)| stem.to_owned());
+
+            match save(stem, &file, sounds).await {
+                Ok(sound) => sounds.update(|sounds| sounds.push(sound)),
+                Err(e) => report_error(
+                    "uploads",
+                    format!("failed importing `{}`:\n{e:?}", file.name()),
+                ),
+            }
+        }
+    });
+
+    rejected
+}
+
+fn validate(file: &File) -> Result<(), String> {
+    let name = file.name();
+    let audio = if file.type_().is_empty() {
+        name.rsplit_once('.')
+            .is_some_and(|(_, extension)| EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+    } else {
+        file.type_().starts_with("audio/")
+    };
+
+    if !audio {
+        Err(format!("`{name}` is not an audio file"))
+    } else if file.size() > MAX_SIZE {
+        Err(format!(
+            "`{name}` is larger than {} MiB",
+            MAX_SIZE / 1024.0 / 1024.0
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Delete a custom sound for good.
+pub fn remove(sound: Sound, sounds: RwSignal<Vec<Sound>>) {
+    sounds.update(|sounds| sounds.retain(|other| *other != sound));
+    sound.forget();
+
+    spawn_local(async move {
+        if let Err(e) = delete(sound.name).await {
+            report_error(
+                "uploads",
+                format!("failed deleting sound `{}`:\n{e:?}", sound.name),
+            );
+        }
+    });
+}
+
+/// Store `audio` under a name based on `name`, that none of the built-in, pack or custom `sounds`
+/// uses yet.
+async fn save(name: String, audio: &Blob, sounds: RwSignal<Vec<Sound>>) -> Result<Sound, JsValue> {
+    let taken = |name: &str| {
+        Sound::in_manifest(name)
+            || sounds.with_untracked(|sounds| sounds.iter().any(|sound| sound.name == name))
+    };
+    let name = iter::once(name.clone())
+        .chain((2..).map(|n| format!("{name} {n}")))
+        .find(|name| !taken(name))
+        .unwrap_throw();
+
+    let store = store(IdbTransactionMode::Readwrite).await?;
+    settle(&store.add_with_key(audio, &name.as_str().into())?).await?;
+
+    let url = Url::create_object_url_with_blob(audio)?;
+    Ok(Sound::custom(name, url))
+}
+
+async fn delete(name: &str) -> Result<(), JsValue> {
+    let store = store(IdbTransactionMode::Readwrite).await?;
+    settle(&store.delete(&name.into())?).await?;
+    Ok(())
+}
+
+async fn load() -> Result<Vec<Sound>, JsValue> {
+    let store = store(IdbTransactionMode::Readonly).await?;
+    // Both requests go out before waiting, as the transaction closes once it has nothing left to
+    // do. Their results are sorted by key, so they line up.
+    let names = settle(&store.get_all_keys()?);
+    let files = settle(&store.get_all()?);
+    let names = names.await?.unchecked_into::<Array>();
+    let files = files.await?.unchecked_into::<Array>();
+
+    names
+        .iter()
+        .zip(files.iter())
+        .map(|(name, file)| {
+            let name = name.as_string().ok_or("sound name is not a string")?;
+            let url = Url::create_object_url_with_blob(file.unchecked_ref())?;
+            Ok(Sound::custom(name, url))
+        })
+        .collect()
+}
+
+async fn store(mode: IdbTransactionMode) -> Result<IdbObjectStore, JsValue> {
+    let factory = window().indexed_db()?.ok_or("IndexedDB is not available")?;
+    let request = factory.open_with_u32(DATABASE, 1)?;
+
+    // Runs before the database opens, if it didn't exist yet.
+    let upgrade = Closure::once_into_js({
+        let request = request.clone();
+        move || -> Result<(), JsValue> {
+            request
+                .result()?
+                .unchecked_into::<IdbDatabase>()
+                .create_object_store(STORE)?;
+            Ok(())
+        }
+    });
+    request.set_onupgradeneeded(Some(upgrade.unchecked_ref()));
+
+    settle(&request)
+        .await?
+        .unchecked_into::<IdbDatabase>()
+        .transaction_with_str_and_mode(STORE, mode)?
+        .object_store(STORE)
+}
+
+/// Wait for an IndexedDB request to finish, and take its result. Results that arrive before the
+/// future is awaited aren't lost.
+fn settle(request: &IdbRequest) -> JsFuture {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let success = Closure::once_into_js({
+            let request = request.clone();
+            move || resolve.call1(&JsValue::NULL, &request.result().unwrap_or_default())
+        });
+        let error = Closure::once_into_js({
+            let request = request.clone();
+            move || {
+                let error = request.error().ok().flatten().map(JsValue::from);
+                reject.call1(&JsValue::NULL, &error.unwrap_or_default())
+            }
+        });
+        request.set_onsuccess(Some(success.unchecked_ref()));
+        request.set_onerror(Some(error.unchecked_ref()));
+    });
+
+    JsFuture::from(promise)
+}